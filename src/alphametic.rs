@@ -0,0 +1,199 @@
+use crate::puzzle::assert_base;
+use crate::solution::{LetterOrder, Solution};
+use crate::solver::{validate_words, Solutions, SolveError, Uniqueness};
+
+/// The settings shared by every kind of puzzle: its numeric base, the
+/// leading-zero rule and the order in which the letters of a solution are listed.
+///
+/// They are changed with the builder methods of [`Alphametic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Settings {
+    pub(crate) base: u32,
+    pub(crate) allow_leading_zeros: bool,
+    pub(crate) letter_order: LetterOrder,
+}
+
+impl Default for Settings {
+    /// Base 10, following the leading-zero rule, with letters in order of first appearance.
+    fn default() -> Self {
+        Settings {
+            base: 10,
+            allow_leading_zeros: false,
+            letter_order: LetterOrder::FirstAppearance,
+        }
+    }
+}
+
+/// A crypto-arithmetic puzzle of any kind: a [`Puzzle`](crate::Puzzle),
+/// [`Multiplication`](crate::Multiplication), [`Division`](crate::Division) or
+/// [`Equation`](crate::Equation).
+///
+/// Each kind provides its words and its search; the settings, validation and
+/// the ways of solving are shared.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{Alphametic, Division, Multiplication, Puzzle};
+///
+/// fn answers(puzzle: &impl Alphametic) -> usize {
+///     puzzle.validate().map_or(0, |()| puzzle.count_solutions())
+/// }
+///
+/// assert_eq!(answers(&"SEND + MORE = MONEY".parse::<Puzzle>().unwrap()), 1);
+/// assert_eq!(answers(&"AB * CD = EFGE; EAE, HA".parse::<Multiplication>().unwrap()), 1);
+/// assert_eq!(answers(&"ABCDEF / GHIJK = LMN".parse::<Division>().unwrap()), 0);
+/// ```
+pub trait Alphametic: Sized {
+    /// Returns every word of the puzzle.
+    fn words(&self) -> Vec<&str>;
+
+    /// Returns an iterator over every solution.
+    fn solutions(&self) -> Solutions;
+
+    /// Returns the settings of the puzzle.
+    fn settings(&self) -> &Settings;
+
+    /// Returns the settings of the puzzle, for the builder methods to change.
+    fn settings_mut(&mut self) -> &mut Settings;
+
+    /// Checks the parts of the puzzle beyond its words, such as the rows of a
+    /// written layout; see [`Alphametic::validate`]. Any layout is accepted by default.
+    fn check_layout(&self) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    /// Relaxes (or restores) the rule that no multi-letter word may start with zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Puzzle};
+    ///
+    /// let puzzle = Puzzle::new(vec!["SEND".to_string(), "MORE".to_string()], "MONEY".to_string())
+    ///     .allow_leading_zeros(true);
+    /// assert_eq!(puzzle.count_solutions(), 25);
+    /// ```
+    fn allow_leading_zeros(mut self, allow: bool) -> Self {
+        self.settings_mut().allow_leading_zeros = allow;
+        self
+    }
+
+    /// Sets the numeric base of the puzzle, from 2 to 36; the default is 10.
+    ///
+    /// A puzzle in base `b` may have up to `b` distinct letters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base` is outside `2..=36`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Puzzle};
+    ///
+    /// let puzzle: Puzzle = "A + A = BC".parse().unwrap();
+    /// let solution = puzzle.with_base(16).solve().unwrap();
+    /// assert_eq!(solution.value("A") * 2, solution.value("BC"));
+    /// assert_eq!(solution.base(), 16);
+    /// ```
+    fn with_base(mut self, base: u32) -> Self {
+        assert_base(base);
+        self.settings_mut().base = base;
+        self
+    }
+
+    /// Sets the order in which the letters of each solution are listed; the
+    /// default is [`LetterOrder::FirstAppearance`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, LetterOrder, Puzzle};
+    ///
+    /// let puzzle: Puzzle = "SEND + MORE = MONEY".parse().unwrap();
+    /// assert_eq!(puzzle.solve().unwrap().letters(), "SENDMORY");
+    /// let puzzle = puzzle.with_letter_order(LetterOrder::Alphabetical);
+    /// assert_eq!(puzzle.solve().unwrap().letters(), "DEMNORSY");
+    /// ```
+    fn with_letter_order(mut self, order: LetterOrder) -> Self {
+        self.settings_mut().letter_order = order;
+        self
+    }
+
+    /// Returns `true` if words may start with a letter mapped to zero.
+    fn allows_leading_zeros(&self) -> bool {
+        self.settings().allow_leading_zeros
+    }
+
+    /// Returns the numeric base of the puzzle.
+    fn base(&self) -> u32 {
+        self.settings().base
+    }
+
+    /// Returns the order in which the letters of each solution are listed.
+    fn letter_order(&self) -> LetterOrder {
+        self.settings().letter_order
+    }
+
+    /// Checks that the puzzle is well-formed: every word is non-empty and made of
+    /// letters, there are no more distinct letters than digits in its base, and
+    /// its layout passes [`Alphametic::check_layout`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Multiplication, Puzzle, SolveError};
+    ///
+    /// let puzzle = Puzzle::new(vec!["SEND".to_string(), "".to_string()], "MONEY".to_string());
+    /// assert_eq!(puzzle.validate(), Err(SolveError::EmptyWord));
+    /// let puzzle = Puzzle::new(vec!["SEND".to_string(), "M0RE".to_string()], "MONEY".to_string());
+    /// assert_eq!(puzzle.validate(), Err(SolveError::InvalidCharacter('0')));
+    /// let puzzle: Multiplication = "ABCDEF * GHIJK = LMN".parse().unwrap();
+    /// assert_eq!(
+    ///     puzzle.validate(),
+    ///     Err(SolveError::TooManyLetters { letters: 14, base: 10 })
+    /// );
+    /// ```
+    fn validate(&self) -> Result<(), SolveError> {
+        validate_words(&self.words(), self.base())?;
+        self.check_layout()
+    }
+
+    /// Returns the first solution found.
+    fn solve(&self) -> Option<Solution> {
+        self.solutions().next()
+    }
+
+    /// Returns the first solution found, or why there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error found by [`Alphametic::validate`], or
+    /// [`SolveError::Unsatisfiable`] if the puzzle is well-formed but has no solution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Equation, Puzzle, SolveError};
+    ///
+    /// let puzzle: Puzzle = "A + B = CDE".parse().unwrap();
+    /// assert_eq!(puzzle.try_solve(), Err(SolveError::Unsatisfiable));
+    /// let equation: Equation = "A * B = CDE".parse().unwrap();
+    /// assert_eq!(equation.try_solve(), Err(SolveError::Unsatisfiable));
+    /// ```
+    fn try_solve(&self) -> Result<Solution, SolveError> {
+        self.validate()?;
+        self.solve().ok_or(SolveError::Unsatisfiable)
+    }
+
+    /// Returns the number of solutions.
+    fn count_solutions(&self) -> usize {
+        self.solutions().count()
+    }
+
+    /// Checks whether the puzzle has exactly one solution, stopping after the second.
+    fn check_uniqueness(&self) -> Uniqueness {
+        self.solutions().uniqueness()
+    }
+}
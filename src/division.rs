@@ -0,0 +1,458 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::alphametic::{Alphametic, Settings};
+use crate::puzzle::{parse_word, split_equation, ParsePuzzleError};
+use crate::search::{
+    add_product_checks, column_order, letter_indices, suffix_ready, word_value, BacktrackSolver,
+};
+use crate::solver::{leading_letters, word_to_number_in_base, Engine, Solutions, SolveError};
+use crate::stats::Rule;
+
+/// One step of the long-division layout: the row subtracted and the row left below it.
+///
+/// Steps are written only for the non-zero digits of the quotient. `product` is
+/// the divisor times that digit, and `difference` is what remains after the
+/// subtraction with the following dividend digits brought down, up to the next
+/// step. The difference of the last step is the remainder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivisionStep {
+    pub product: String,
+    pub difference: String,
+}
+
+impl DivisionStep {
+    /// Creates a step from its product and difference rows.
+    pub fn new(product: impl Into<String>, difference: impl Into<String>) -> Self {
+        DivisionStep {
+            product: product.into(),
+            difference: difference.into(),
+        }
+    }
+}
+
+/// A long-division puzzle such as `DIVIDEND / DIVISOR = QUOTIENT`.
+///
+/// Without a remainder the division must be exact. The intermediate rows of the
+/// classic layout can be given as [`DivisionStep`]s, and every row is then
+/// checked as well as the final equation.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{Alphametic, Division};
+///
+/// let puzzle: Division = "abcb / de = fe r g".parse().unwrap();
+/// assert_eq!(puzzle.to_string(), "ABCB / DE = FE R G");
+/// for solution in puzzle.solutions() {
+///     let remainder: u128 = solution.value("G");
+///     assert_eq!(solution.value("DE") * solution.value("FE") + remainder, solution.value("ABCB"));
+///     assert!(remainder < solution.value("DE"));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Division {
+    dividend: String,
+    divisor: String,
+    quotient: String,
+    remainder: Option<String>,
+    steps: Vec<DivisionStep>,
+    settings: Settings,
+}
+
+impl Division {
+    /// Creates an exact division puzzle without intermediate rows, following the leading-zero rule.
+    ///
+    /// # Parameters
+    ///
+    /// - `dividend`: The word that is divided.
+    /// - `divisor`: The word it is divided by.
+    /// - `quotient`: The result word.
+    pub fn new(dividend: String, divisor: String, quotient: String) -> Self {
+        Division {
+            dividend,
+            divisor,
+            quotient,
+            remainder: None,
+            steps: Vec::new(),
+            settings: Settings::default(),
+        }
+    }
+
+    /// Sets the remainder word; it must be smaller than the divisor.
+    pub fn with_remainder(mut self, remainder: String) -> Self {
+        self.remainder = Some(remainder);
+        self
+    }
+
+    /// Sets the intermediate rows, one step per non-zero quotient digit, from the top.
+    ///
+    /// More steps than quotient digits are reported by [`Alphametic::validate`]
+    /// as [`SolveError::DivisionSteps`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Division, DivisionStep};
+    ///
+    /// // 1081 / 23 = 47: 108 - 92 = 16, then 161 - 161 = 0.
+    /// let puzzle = Division::new("ABCA".to_string(), "DE".to_string(), "FG".to_string());
+    /// assert!(puzzle.count_solutions() > 1);
+    /// let puzzle = puzzle.with_steps(vec![DivisionStep::new("HD", "AIA"), DivisionStep::new("AIA", "B")]);
+    /// assert_eq!(puzzle.count_solutions(), 1);
+    /// assert_eq!(puzzle.solve().unwrap().value("ABCA"), 1081);
+    /// ```
+    pub fn with_steps(mut self, steps: Vec<DivisionStep>) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Returns the word that is divided.
+    pub fn dividend(&self) -> &str {
+        &self.dividend
+    }
+
+    /// Returns the word the dividend is divided by.
+    pub fn divisor(&self) -> &str {
+        &self.divisor
+    }
+
+    /// Returns the quotient word.
+    pub fn quotient(&self) -> &str {
+        &self.quotient
+    }
+
+    /// Returns the remainder word, or `None` if the division must be exact.
+    pub fn remainder(&self) -> Option<&str> {
+        self.remainder.as_deref()
+    }
+
+    /// Returns the intermediate rows of the layout.
+    pub fn steps(&self) -> &[DivisionStep] {
+        &self.steps
+    }
+
+    /// Checks whether `mapping` satisfies the puzzle in its base; see [`is_valid_division`].
+    pub fn is_solved_by(&self, mapping: &[(char, u32)]) -> bool {
+        is_valid_division_in_base(
+            &self.dividend,
+            &self.divisor,
+            &self.quotient,
+            self.remainder.as_deref(),
+            &self.steps,
+            mapping,
+            self.settings.base,
+        )
+    }
+}
+
+impl Alphametic for Division {
+    /// Returns every word of the puzzle: the dividend, divisor, quotient, remainder
+    /// and the rows of the layout, top to bottom.
+    fn words(&self) -> Vec<&str> {
+        let mut words: Vec<&str> = vec![&self.dividend, &self.divisor, &self.quotient];
+        words.extend(self.remainder.as_deref());
+        for step in &self.steps {
+            words.push(&step.product);
+            words.push(&step.difference);
+        }
+        words
+    }
+
+    /// Returns an iterator over every solution.
+    ///
+    /// The equation `divisor * quotient + remainder = dividend` is checked column
+    /// by column from the least significant digit, as for multiplication; the
+    /// remainder is checked against the divisor once both are assigned, and the
+    /// intermediate rows once every letter has a digit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Division};
+    ///
+    /// let puzzle: Division = "AB / C = D R E".parse().unwrap();
+    /// let solutions: Vec<_> = puzzle.solutions().collect();
+    /// assert!(solutions.iter().all(|solution| puzzle.is_solved_by(solution.mapping())));
+    /// assert_eq!(puzzle.count_solutions(), solutions.len());
+    /// assert_eq!(puzzle.count_solutions(), 109);
+    /// ```
+    fn solutions(&self) -> Solutions {
+        let words: Vec<&str> = self.words();
+        let letters: Vec<char> = column_order(&words);
+
+        let puzzle: Division = self.clone();
+        let verify = Box::new(move |mapping: &[(char, u32)]| puzzle.is_solved_by(mapping));
+        if letters.len() > self.settings.base as usize || self.check_layout().is_err() {
+            return Solutions::from_engine(letters, None, verify, self.settings.base);
+        }
+
+        let leading: HashSet<char> = if self.settings.allow_leading_zeros {
+            HashSet::new()
+        } else {
+            let rows: Vec<String> = words[1..].iter().map(|word| word.to_string()).collect();
+            leading_letters(&rows, &self.dividend)
+        };
+        let mut solver = BacktrackSolver::new(
+            letters.iter().map(|c| leading.contains(c)).collect(),
+            self.settings.base,
+        );
+
+        let divisor: Vec<usize> = letter_indices(&self.divisor, &letters);
+        let remainder: Vec<usize> = match &self.remainder {
+            Some(remainder) => letter_indices(remainder, &letters),
+            None => Vec::new(),
+        };
+        add_product_checks(
+            &mut solver,
+            &divisor,
+            &letter_indices(&self.quotient, &letters),
+            &remainder,
+            &letter_indices(&self.dividend, &letters),
+            self.settings.base,
+        );
+        if !remainder.is_empty() {
+            let base: u32 = self.settings.base;
+            solver.add_check(
+                suffix_ready(&[&divisor, &remainder], usize::MAX),
+                Rule::Value,
+                Arc::new(move |assignment| {
+                    let remainder: Option<u128> = word_value(&remainder, assignment, base);
+                    let divisor: Option<u128> = word_value(&divisor, assignment, base);
+                    matches!((remainder, divisor), (Some(remainder), Some(divisor)) if remainder < divisor)
+                }),
+            );
+        }
+        if !self.steps.is_empty() {
+            let puzzle: Division = self.clone();
+            let order: Vec<char> = letters.clone();
+            solver.add_check(
+                letters.len() - 1,
+                Rule::Value,
+                Arc::new(move |assignment| {
+                    let mapping: Vec<(char, u32)> = order
+                        .iter()
+                        .cloned()
+                        .zip(assignment.iter().cloned())
+                        .collect();
+                    puzzle.is_solved_by(&mapping)
+                }),
+            );
+        }
+
+        Solutions::from_engine(
+            letters,
+            Some(Engine::Backtrack(solver)),
+            verify,
+            self.settings.base,
+        )
+        .ordered(&self.settings.letter_order.arrange(&words))
+    }
+
+    fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// Checks that there are no more steps than digits in the quotient.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Division, DivisionStep, SolveError};
+    ///
+    /// let puzzle = Division::new("ABC".to_string(), "D".to_string(), "E".to_string())
+    ///     .with_steps(vec![DivisionStep::new("F", "G"), DivisionStep::new("H", "I")]);
+    /// assert_eq!(
+    ///     puzzle.validate(),
+    ///     Err(SolveError::DivisionSteps { steps: 2, digits: 1 })
+    /// );
+    /// ```
+    fn check_layout(&self) -> Result<(), SolveError> {
+        let digits: usize = self.quotient.chars().count();
+        if self.steps.len() > digits {
+            return Err(SolveError::DivisionSteps {
+                steps: self.steps.len(),
+                digits,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Checks if the current mapping satisfies a long-division puzzle.
+///
+/// The division is redone digit by digit, and when `steps` is not empty each of
+/// its rows must match the product and difference written at that point of the
+/// classic layout.
+///
+/// # Parameters
+///
+/// - `dividend`: The word that is divided.
+/// - `divisor`: The word it is divided by.
+/// - `quotient`: The result word.
+/// - `remainder`: The remainder word, or `None` if the division must be exact.
+/// - `steps`: The intermediate rows, or an empty slice if the puzzle has none.
+/// - `mapping`: A slice of tuples, each containing a character and its corresponding digit.
+///
+/// # Returns
+///
+/// `true` if the solution is valid, otherwise `false`.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{is_valid_division, DivisionStep};
+///
+/// // 1081 / 23 = 47: 108 - 92 = 16, then 161 - 161 = 0.
+/// let mapping = [('A', 1), ('B', 0), ('C', 8), ('D', 2), ('E', 3), ('F', 4), ('G', 7), ('H', 9), ('I', 6)];
+/// let steps = [DivisionStep::new("HD", "AIA"), DivisionStep::new("AIA", "B")];
+/// assert!(is_valid_division("ABCA", "DE", "FG", None, &steps, &mapping));
+/// assert!(!is_valid_division("ABCA", "DE", "FG", None, &steps[..1], &mapping));
+/// ```
+pub fn is_valid_division(
+    dividend: &str,
+    divisor: &str,
+    quotient: &str,
+    remainder: Option<&str>,
+    steps: &[DivisionStep],
+    mapping: &[(char, u32)],
+) -> bool {
+    is_valid_division_in_base(dividend, divisor, quotient, remainder, steps, mapping, 10)
+}
+
+/// Like [`is_valid_division`], for a puzzle in `base`.
+fn is_valid_division_in_base(
+    dividend: &str,
+    divisor: &str,
+    quotient: &str,
+    remainder: Option<&str>,
+    steps: &[DivisionStep],
+    mapping: &[(char, u32)],
+    base: u32,
+) -> bool {
+    let value = |word: &str| word_to_number_in_base(word, mapping, base).ok();
+    let (Some(dividend_value), Some(divisor_value), Some(quotient_value)) =
+        (value(dividend), value(divisor), value(quotient))
+    else {
+        return false;
+    };
+    let Some(remainder_value) = remainder.map_or(Some(0), value) else {
+        return false;
+    };
+    if divisor_value == 0
+        || dividend_value / divisor_value != quotient_value
+        || dividend_value % divisor_value != remainder_value
+    {
+        return false;
+    }
+    if steps.is_empty() {
+        return true;
+    }
+
+    let mut products: Vec<u128> = Vec::new();
+    let mut partials: Vec<u128> = Vec::new();
+    let mut current: u128 = 0;
+    for c in dividend.chars() {
+        let Some(next) = current
+            .checked_mul(u128::from(base))
+            .zip(value(&c.to_string()))
+            .and_then(|(shifted, digit)| shifted.checked_add(digit))
+        else {
+            return false;
+        };
+        current = next;
+        let digit: u128 = current / divisor_value;
+        if digit > 0 {
+            products.push(digit * divisor_value);
+            partials.push(current);
+            current -= digit * divisor_value;
+        }
+    }
+    let differences = partials.into_iter().skip(1).chain(std::iter::once(current));
+    steps.len() == products.len()
+        && steps
+            .iter()
+            .zip(products)
+            .zip(differences)
+            .all(|((step, product), difference)| {
+                value(&step.product) == Some(product) && value(&step.difference) == Some(difference)
+            })
+}
+
+impl fmt::Display for Division {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} / {} = {}",
+            self.dividend, self.divisor, self.quotient
+        )?;
+        if let Some(remainder) = &self.remainder {
+            write!(f, " R {}", remainder)?;
+        }
+        for (i, step) in self.steps.iter().enumerate() {
+            let separator: &str = if i == 0 { "; " } else { ", " };
+            write!(f, "{}{}, {}", separator, step.product, step.difference)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Division {
+    type Err = ParsePuzzleError;
+
+    /// Parses an equation such as `"ABCD / EF = GH"` or, with a remainder, `"ABCD / EF = GH R I"`.
+    ///
+    /// The intermediate rows may follow a `;`, separated by commas and listed top
+    /// to bottom as in the written layout: product, difference, product, difference.
+    /// Whitespace is ignored and letters are converted to upper case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Division, DivisionStep, ParsePuzzleError};
+    ///
+    /// let puzzle: Division = "ABCA / DE = FG; HD, AIA, AIA, B".parse().unwrap();
+    /// assert_eq!(puzzle.steps()[1], DivisionStep::new("AIA", "B"));
+    /// assert_eq!("AB / C = D; E".parse::<Division>(), Err(ParsePuzzleError::UnpairedRow));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (equation, rows) = match s.split_once(';') {
+            Some((equation, rows)) => (equation, Some(rows)),
+            None => (s, None),
+        };
+        let (left, right) = split_equation(equation)?;
+        let (dividend, divisor) = left
+            .split_once('/')
+            .ok_or(ParsePuzzleError::MissingOperator('/'))?;
+        let mut division = match right.split_whitespace().collect::<Vec<&str>>()[..] {
+            [quotient, r, remainder] if r.eq_ignore_ascii_case("R") => Division::new(
+                parse_word(dividend)?,
+                parse_word(divisor)?,
+                parse_word(quotient)?,
+            )
+            .with_remainder(parse_word(remainder)?),
+            _ => Division::new(
+                parse_word(dividend)?,
+                parse_word(divisor)?,
+                parse_word(right)?,
+            ),
+        };
+        if let Some(rows) = rows {
+            let rows: Vec<String> = rows.split(',').map(parse_word).collect::<Result<_, _>>()?;
+            if !rows.len().is_multiple_of(2) {
+                return Err(ParsePuzzleError::UnpairedRow);
+            }
+            division.steps = rows
+                .chunks(2)
+                .map(|pair| DivisionStep::new(pair[0].clone(), pair[1].clone()))
+                .collect();
+        }
+        Ok(division)
+    }
+}
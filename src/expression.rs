@@ -0,0 +1,597 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::alphametic::{Alphametic, Settings};
+use crate::puzzle::{ParsePuzzleError, Sign, Term};
+use crate::search::{
+    column_order, letter_indices, max_modular_column, suffix_ready, suffix_value, word_value,
+    BacktrackSolver,
+};
+use crate::solver::{leading_letters, Engine, Solutions};
+use crate::stats::Rule;
+
+/// An arithmetic expression over words, as found on one side of an [`Equation`].
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::Expr;
+///
+/// let expr = Expr::Add(
+///     Box::new(Expr::Mul(Box::new(Expr::word("AB")), Box::new(Expr::word("C")))),
+///     Box::new(Expr::word("DE")),
+/// );
+/// assert_eq!(expr.to_string(), "AB * C + DE");
+/// assert_eq!(expr.evaluate(&[('A', 1), ('B', 2), ('C', 3), ('D', 4), ('E', 5)]), Some(81));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Word(String),
+    Number(u64),
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Creates a word expression.
+    pub fn word(word: impl Into<String>) -> Self {
+        Expr::Word(word.into())
+    }
+
+    /// Evaluates the expression under `mapping`, with exact integer semantics.
+    ///
+    /// # Returns
+    ///
+    /// The value of the expression, or `None` if a word contains a letter that is
+    /// missing from `mapping`, a division is not exact, divides by zero, or an
+    /// intermediate value overflows an `i128`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::Equation;
+    ///
+    /// let equation: Equation = "AB / C = D".parse().unwrap();
+    /// let side = &equation.sides()[0];
+    /// assert_eq!(side.evaluate(&[('A', 1), ('B', 2), ('C', 4)]), Some(3));
+    /// assert_eq!(side.evaluate(&[('A', 1), ('B', 2), ('C', 5)]), None);
+    /// assert_eq!(side.evaluate(&[('A', 1), ('B', 2)]), None);
+    /// ```
+    pub fn evaluate(&self, mapping: &[(char, u32)]) -> Option<i128> {
+        self.evaluate_in_base(mapping, 10)
+    }
+
+    /// Evaluates the expression under `mapping`, reading words as numbers in `base`.
+    ///
+    /// Constants are always written in decimal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::Expr;
+    ///
+    /// let expr = Expr::Mul(Box::new(Expr::word("AB")), Box::new(Expr::Number(2)));
+    /// assert_eq!(expr.evaluate_in_base(&[('A', 1), ('B', 15)], 16), Some(0x3E));
+    /// ```
+    pub fn evaluate_in_base(&self, mapping: &[(char, u32)], base: u32) -> Option<i128> {
+        let evaluate = |expr: &Expr| expr.evaluate_in_base(mapping, base);
+        match self {
+            Expr::Word(word) => word.chars().try_fold(0i128, |number, c| {
+                let digit: u32 = mapping.iter().find(|&&(ch, _)| ch == c)?.1;
+                number
+                    .checked_mul(i128::from(base))?
+                    .checked_add(i128::from(digit))
+            }),
+            Expr::Number(number) => Some(i128::from(*number)),
+            Expr::Neg(inner) => evaluate(inner)?.checked_neg(),
+            Expr::Add(left, right) => evaluate(left)?.checked_add(evaluate(right)?),
+            Expr::Sub(left, right) => evaluate(left)?.checked_sub(evaluate(right)?),
+            Expr::Mul(left, right) => evaluate(left)?.checked_mul(evaluate(right)?),
+            Expr::Div(left, right) => exact_div(evaluate(left)?, evaluate(right)?),
+        }
+    }
+
+    /// Returns every word of the expression, from left to right.
+    pub fn words(&self) -> Vec<&str> {
+        match self {
+            Expr::Word(word) => vec![word.as_str()],
+            Expr::Number(_) => Vec::new(),
+            Expr::Neg(inner) => inner.words(),
+            Expr::Add(left, right)
+            | Expr::Sub(left, right)
+            | Expr::Mul(left, right)
+            | Expr::Div(left, right) => {
+                let mut words: Vec<&str> = left.words();
+                words.extend(right.words());
+                words
+            }
+        }
+    }
+
+    fn has_division(&self) -> bool {
+        match self {
+            Expr::Word(_) | Expr::Number(_) => false,
+            Expr::Neg(inner) => inner.has_division(),
+            Expr::Div(_, _) => true,
+            Expr::Add(left, right) | Expr::Sub(left, right) | Expr::Mul(left, right) => {
+                left.has_division() || right.has_division()
+            }
+        }
+    }
+
+    /// Binding strength used to decide where parentheses are needed when printing.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Add(_, _) | Expr::Sub(_, _) => 1,
+            Expr::Mul(_, _) | Expr::Div(_, _) => 2,
+            Expr::Word(_) | Expr::Number(_) | Expr::Neg(_) => 3,
+        }
+    }
+}
+
+/// Builds the expression adding up signed `terms`, or `0` if there are none.
+pub(crate) fn terms_expr(terms: &[Term]) -> Expr {
+    let mut terms = terms.iter();
+    let Some(first) = terms.next() else {
+        return Expr::Number(0);
+    };
+    let first: Expr = match first.sign {
+        Sign::Plus => Expr::word(first.word.as_str()),
+        Sign::Minus => Expr::Neg(Box::new(Expr::word(first.word.as_str()))),
+    };
+    terms.fold(first, |left, term| {
+        let right: Box<Expr> = Box::new(Expr::word(term.word.as_str()));
+        match term.sign {
+            Sign::Plus => Expr::Add(Box::new(left), right),
+            Sign::Minus => Expr::Sub(Box::new(left), right),
+        }
+    })
+}
+
+/// Divides `left` by `right`, returning `None` unless the division is exact.
+fn exact_div(left: i128, right: i128) -> Option<i128> {
+    if right == 0 || left.checked_rem(right)? != 0 {
+        return None;
+    }
+    left.checked_div(right)
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (left, op, right) = match self {
+            Expr::Word(word) => return write!(f, "{}", word),
+            Expr::Number(number) => return write!(f, "{}", number),
+            Expr::Neg(inner) if inner.precedence() < 3 => return write!(f, "-({})", inner),
+            Expr::Neg(inner) => return write!(f, "-{}", inner),
+            Expr::Add(left, right) => (left, '+', right),
+            Expr::Sub(left, right) => (left, '-', right),
+            Expr::Mul(left, right) => (left, '*', right),
+            Expr::Div(left, right) => (left, '/', right),
+        };
+        if left.precedence() < self.precedence() {
+            write!(f, "({})", left)?;
+        } else {
+            write!(f, "{}", left)?;
+        }
+        write!(f, " {} ", op)?;
+        // Subtraction and division are not associative, so `A - (B - C)` keeps its parentheses.
+        let associative: bool = matches!(op, '+' | '*');
+        if right.precedence() < self.precedence()
+            || (!associative && right.precedence() == self.precedence())
+        {
+            write!(f, "({})", right)
+        } else {
+            write!(f, "{}", right)
+        }
+    }
+}
+
+/// An equation of two or more expressions that must all have the same value.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{Alphametic, Equation};
+///
+/// let equation: Equation = "ab * c + de = fgh".parse().unwrap();
+/// assert_eq!(equation.to_string(), "AB * C + DE = FGH");
+/// let solution = equation.solve().unwrap();
+/// assert_eq!(
+///     solution.value("AB") * solution.value("C") + solution.value("DE"),
+///     solution.value("FGH"),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equation {
+    sides: Vec<Expr>,
+    settings: Settings,
+}
+
+impl Equation {
+    /// Creates an equation from its sides, following the leading-zero rule.
+    pub fn new(sides: Vec<Expr>) -> Self {
+        Equation {
+            sides,
+            settings: Settings::default(),
+        }
+    }
+
+    /// Returns the sides of the equation, from left to right.
+    pub fn sides(&self) -> &[Expr] {
+        &self.sides
+    }
+
+    /// Checks whether every side evaluates to the same value under `mapping`, in the equation's base.
+    ///
+    /// A side whose value is undefined, such as an inexact division or a letter
+    /// missing from an incomplete mapping, never matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::Equation;
+    ///
+    /// let equation: Equation = "AB + C = D".parse().unwrap();
+    /// assert!(equation.is_solved_by(&[('A', 0), ('B', 7), ('C', 1), ('D', 8)]));
+    /// assert!(!equation.is_solved_by(&[('A', 1)]));
+    /// ```
+    pub fn is_solved_by(&self, mapping: &[(char, u32)]) -> bool {
+        let mut values = self
+            .sides
+            .iter()
+            .map(|side| side.evaluate_in_base(mapping, self.settings.base));
+        match values.next() {
+            Some(Some(first)) => values.all(|value| value == Some(first)),
+            _ => false,
+        }
+    }
+}
+
+impl Alphametic for Equation {
+    /// Returns every word of the equation, from left to right.
+    fn words(&self) -> Vec<&str> {
+        self.sides.iter().flat_map(Expr::words).collect()
+    }
+
+    /// Returns an iterator over every solution.
+    ///
+    /// Letters are assigned column by column from the least significant digit.
+    /// When the equation has no division, addition, subtraction and
+    /// multiplication all carry over to arithmetic modulo `base^k`, so the sides
+    /// are compared on their last `k` digits as soon as those letters are known.
+    fn solutions(&self) -> Solutions {
+        let words: Vec<&str> = self.words();
+        let letters: Vec<char> = column_order(&words);
+
+        let equation: Equation = self.clone();
+        let verify = Box::new(move |mapping: &[(char, u32)]| equation.is_solved_by(mapping));
+        if letters.len() > self.settings.base as usize
+            || (letters.is_empty() && !self.is_solved_by(&[]))
+        {
+            return Solutions::from_engine(letters, None, verify, self.settings.base);
+        }
+
+        let leading: HashSet<char> = if self.settings.allow_leading_zeros || words.is_empty() {
+            HashSet::new()
+        } else {
+            let (last, rest) = words.split_last().unwrap();
+            let rest: Vec<String> = rest.iter().map(|word| word.to_string()).collect();
+            leading_letters(&rest, last)
+        };
+        let mut solver = BacktrackSolver::new(
+            letters.iter().map(|c| leading.contains(c)).collect(),
+            self.settings.base,
+        );
+        if letters.is_empty() {
+            return Solutions::from_engine(
+                letters,
+                Some(Engine::Backtrack(solver)),
+                verify,
+                self.settings.base,
+            );
+        }
+
+        let sides: Vec<Node> = self
+            .sides
+            .iter()
+            .map(|side| Node::compile(side, &letters))
+            .collect();
+        let indices: Vec<Vec<usize>> = words
+            .iter()
+            .map(|word| letter_indices(word, &letters))
+            .collect();
+        let indices: Vec<&[usize]> = indices.iter().map(Vec::as_slice).collect();
+        let base: u32 = self.settings.base;
+        if !self.sides.iter().any(Expr::has_division) {
+            let width: usize = indices.iter().map(|word| word.len()).max().unwrap_or(0);
+            for k in 1..=width.min(max_modular_column(base)) {
+                let sides: Vec<Node> = sides.clone();
+                let modulus: i128 = i128::from(base).pow(k as u32);
+                solver.add_check(
+                    suffix_ready(&indices, k),
+                    Rule::Suffix,
+                    Arc::new(move |assignment| {
+                        let first: i128 = sides[0].evaluate_mod(k, modulus, assignment, base);
+                        sides[1..]
+                            .iter()
+                            .all(|side| side.evaluate_mod(k, modulus, assignment, base) == first)
+                    }),
+                );
+            }
+        }
+        solver.add_check(
+            letters.len() - 1,
+            Rule::Value,
+            Arc::new(move |assignment| {
+                let mut values = sides.iter().map(|side| side.evaluate(assignment, base));
+                match values.next() {
+                    Some(Some(first)) => values.all(|value| value == Some(first)),
+                    _ => false,
+                }
+            }),
+        );
+
+        Solutions::from_engine(
+            letters,
+            Some(Engine::Backtrack(solver)),
+            verify,
+            self.settings.base,
+        )
+        .ordered(&self.settings.letter_order.arrange(&words))
+    }
+
+    fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+}
+
+/// An [`Expr`] with its words resolved to letter indices, for fast evaluation during search.
+#[derive(Clone)]
+enum Node {
+    Word(Vec<usize>),
+    Number(i128),
+    Neg(Box<Node>),
+    Add(Box<Node>, Box<Node>),
+    Sub(Box<Node>, Box<Node>),
+    Mul(Box<Node>, Box<Node>),
+    Div(Box<Node>, Box<Node>),
+}
+
+impl Node {
+    fn compile(expr: &Expr, letters: &[char]) -> Self {
+        let compile = |expr: &Expr| Box::new(Node::compile(expr, letters));
+        match expr {
+            Expr::Word(word) => Node::Word(letter_indices(word, letters)),
+            Expr::Number(number) => Node::Number(i128::from(*number)),
+            Expr::Neg(inner) => Node::Neg(compile(inner)),
+            Expr::Add(left, right) => Node::Add(compile(left), compile(right)),
+            Expr::Sub(left, right) => Node::Sub(compile(left), compile(right)),
+            Expr::Mul(left, right) => Node::Mul(compile(left), compile(right)),
+            Expr::Div(left, right) => Node::Div(compile(left), compile(right)),
+        }
+    }
+
+    fn evaluate(&self, assignment: &[u32], base: u32) -> Option<i128> {
+        let evaluate = |node: &Node| node.evaluate(assignment, base);
+        match self {
+            Node::Word(word) => {
+                word_value(word, assignment, base).and_then(|value| i128::try_from(value).ok())
+            }
+            Node::Number(number) => Some(*number),
+            Node::Neg(inner) => evaluate(inner)?.checked_neg(),
+            Node::Add(left, right) => evaluate(left)?.checked_add(evaluate(right)?),
+            Node::Sub(left, right) => evaluate(left)?.checked_sub(evaluate(right)?),
+            Node::Mul(left, right) => evaluate(left)?.checked_mul(evaluate(right)?),
+            Node::Div(left, right) => exact_div(evaluate(left)?, evaluate(right)?),
+        }
+    }
+
+    /// Evaluates a division-free expression modulo `modulus = base^k`, reading only
+    /// the last `k` digits of each word.
+    fn evaluate_mod(&self, k: usize, modulus: i128, assignment: &[u32], base: u32) -> i128 {
+        let evaluate = |node: &Node| node.evaluate_mod(k, modulus, assignment, base);
+        match self {
+            Node::Word(word) => suffix_value(word, k, assignment, base) as i128,
+            Node::Number(number) => number.rem_euclid(modulus),
+            Node::Neg(inner) => (-evaluate(inner)).rem_euclid(modulus),
+            Node::Add(left, right) => (evaluate(left) + evaluate(right)).rem_euclid(modulus),
+            Node::Sub(left, right) => (evaluate(left) - evaluate(right)).rem_euclid(modulus),
+            Node::Mul(left, right) => (evaluate(left) * evaluate(right)).rem_euclid(modulus),
+            Node::Div(_, _) => unreachable!("modular checks are only added without division"),
+        }
+    }
+}
+
+impl fmt::Display for Equation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, side) in self.sides.iter().enumerate() {
+            if i > 0 {
+                write!(f, " = ")?;
+            }
+            write!(f, "{}", side)?;
+        }
+        Ok(())
+    }
+}
+
+/// A lexical token of an equation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Number(u64),
+    Op(char),
+    Open,
+    Close,
+    Equals,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{}", word),
+            Token::Number(number) => write!(f, "{}", number),
+            Token::Op(op) => write!(f, "{}", op),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::Equals => write!(f, "="),
+        }
+    }
+}
+
+/// Splits an equation into tokens, converting words to upper case.
+fn tokenize(s: &str) -> Result<Vec<Token>, ParsePuzzleError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_alphabetic() {
+            let mut word: String = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_alphabetic()) {
+                word.extend(c.to_uppercase());
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        } else if c.is_ascii_digit() {
+            let mut digits: String = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                digits.push(c);
+                chars.next();
+            }
+            let number: u64 = digits
+                .parse()
+                .map_err(|_| ParsePuzzleError::UnexpectedToken(digits))?;
+            tokens.push(Token::Number(number));
+        } else {
+            tokens.push(match c {
+                '+' | '-' | '*' | '/' => Token::Op(c),
+                '(' => Token::Open,
+                ')' => Token::Close,
+                '=' => Token::Equals,
+                _ => return Err(ParsePuzzleError::InvalidCharacter(c)),
+            });
+            chars.next();
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over the tokens of an equation.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Consumes the next token if it is one of the operators in `ops`.
+    fn next_op(&mut self, ops: &[char]) -> Option<char> {
+        match self.peek() {
+            Some(&Token::Op(op)) if ops.contains(&op) => {
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn equation(&mut self) -> Result<Equation, ParsePuzzleError> {
+        let mut sides: Vec<Expr> = vec![self.expr()?];
+        while self.peek() == Some(&Token::Equals) {
+            self.pos += 1;
+            sides.push(self.expr()?);
+        }
+        match self.peek() {
+            None if sides.len() < 2 => Err(ParsePuzzleError::MissingEquals),
+            None => Ok(Equation::new(sides)),
+            Some(Token::Close) => Err(ParsePuzzleError::UnbalancedParenthesis),
+            Some(token) => Err(ParsePuzzleError::UnexpectedToken(token.to_string())),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParsePuzzleError> {
+        let mut left: Expr = self.term()?;
+        while let Some(op) = self.next_op(&['+', '-']) {
+            let right: Box<Expr> = Box::new(self.term()?);
+            left = match op {
+                '+' => Expr::Add(Box::new(left), right),
+                _ => Expr::Sub(Box::new(left), right),
+            };
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, ParsePuzzleError> {
+        let mut left: Expr = self.factor()?;
+        while let Some(op) = self.next_op(&['*', '/']) {
+            let right: Box<Expr> = Box::new(self.factor()?);
+            left = match op {
+                '*' => Expr::Mul(Box::new(left), right),
+                _ => Expr::Div(Box::new(left), right),
+            };
+        }
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> Result<Expr, ParsePuzzleError> {
+        let token: Option<Token> = self.peek().cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Word(word)) => Ok(Expr::Word(word)),
+            Some(Token::Number(number)) => Ok(Expr::Number(number)),
+            Some(Token::Op('-')) => Ok(Expr::Neg(Box::new(self.factor()?))),
+            Some(Token::Open) => {
+                let inner: Expr = self.expr()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(ParsePuzzleError::UnbalancedParenthesis);
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(Token::Close) => Err(ParsePuzzleError::UnbalancedParenthesis),
+            _ => Err(ParsePuzzleError::EmptyWord),
+        }
+    }
+}
+
+impl FromStr for Equation {
+    type Err = ParsePuzzleError;
+
+    /// Parses an equation with `+ - * /`, parentheses and two or more sides.
+    ///
+    /// Multiplication and division bind tighter than addition and subtraction,
+    /// and operators of the same strength group from the left. Whole numbers
+    /// may appear as constants. Whitespace is ignored and letters are converted
+    /// to upper case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Equation, ParsePuzzleError};
+    ///
+    /// let equation: Equation = "(a + b) * 2 = cd = e * f".parse().unwrap();
+    /// assert_eq!(equation.sides().len(), 3);
+    /// assert_eq!(equation.to_string(), "(A + B) * 2 = CD = E * F");
+    /// assert_eq!("A * (B + C = D".parse::<Equation>(), Err(ParsePuzzleError::UnbalancedParenthesis));
+    /// assert_eq!("A + = B".parse::<Equation>(), Err(ParsePuzzleError::EmptyWord));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+        };
+        parser.equation()
+    }
+}
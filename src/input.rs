@@ -0,0 +1,150 @@
+use std::fmt;
+use std::io;
+
+/// An error returned by [`try_input!`](crate::try_input) when no value could be read.
+#[derive(Debug)]
+pub enum InputError {
+    /// Reading from stdin or flushing the prompt failed.
+    Io(io::Error),
+    /// Stdin was closed before a line was entered.
+    Eof,
+    /// The line could not be parsed into the requested type; holds the parser's message.
+    Parse(String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Io(error) => write!(f, "failed to read input: {}", error),
+            InputError::Eof => write!(f, "unexpected end of input"),
+            InputError::Parse(message) => write!(f, "cannot parse input: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for InputError {
+    fn from(error: io::Error) -> Self {
+        InputError::Io(error)
+    }
+}
+
+/// A macro to prompt for user input with an optional message.
+///
+/// # Panics
+///
+/// Panics if stdin cannot be read or is closed, or if the line cannot be parsed;
+/// use [`try_input!`](crate::try_input) or [`input_retry!`](crate::input_retry)
+/// to handle these cases instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crypto_aritmatic::input;
+///
+/// let name: String = input!("Enter your name: ");
+/// println!("Hello, {}!", name);
+/// ```
+#[macro_export]
+macro_rules! input {
+    ($input_type:ty) => {
+        $crate::input!($input_type, "")
+    };
+    () => {
+        $crate::input!(String, "")
+    };
+    ($msg:expr) => {
+        $crate::input!(String, $msg)
+    };
+    ($input_type:ty, $msg:expr) => {
+        match $crate::try_input!($input_type, $msg) {
+            Ok(r) => r,
+            Err(error) => panic!("{}", error),
+        }
+    };
+}
+
+/// Like [`input!`](crate::input), but returns a `Result<T, InputError>` instead of panicking.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crypto_aritmatic::{try_input, InputError};
+///
+/// match try_input!(u32, "Base? ") {
+///     Ok(base) => println!("Solving in base {}", base),
+///     Err(InputError::Eof) => println!("No input given."),
+///     Err(error) => println!("{}", error),
+/// }
+/// ```
+#[macro_export]
+macro_rules! try_input {
+    ($input_type:ty) => {
+        $crate::try_input!($input_type, "")
+    };
+    () => {
+        $crate::try_input!(String, "")
+    };
+    ($msg:expr) => {
+        $crate::try_input!(String, $msg)
+    };
+    ($input_type:ty, $msg:expr) => {
+        (|| -> Result<$input_type, $crate::InputError> {
+            use std::io::{self, Write};
+            print!("{}", $msg);
+            io::stdout().flush()?;
+            let mut user_input = String::new();
+            if io::stdin().read_line(&mut user_input)? == 0 {
+                return Err($crate::InputError::Eof);
+            }
+            user_input
+                .trim()
+                .parse::<$input_type>()
+                .map_err(|error| $crate::InputError::Parse(error.to_string()))
+        })()
+    };
+}
+
+/// Like [`try_input!`](crate::try_input), but prints the parse error and prompts
+/// again until a valid value is entered.
+///
+/// Only a closed stdin or a read error ends the loop with an `Err`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crypto_aritmatic::input_retry;
+///
+/// let base: u32 = input_retry!(u32, "Base? ").expect("stdin was closed");
+/// println!("Solving in base {}", base);
+/// ```
+#[macro_export]
+macro_rules! input_retry {
+    ($input_type:ty) => {
+        $crate::input_retry!($input_type, "")
+    };
+    () => {
+        $crate::input_retry!(String, "")
+    };
+    ($msg:expr) => {
+        $crate::input_retry!(String, $msg)
+    };
+    ($input_type:ty, $msg:expr) => {
+        loop {
+            match $crate::try_input!($input_type, $msg) {
+                Err($crate::InputError::Parse(message)) => {
+                    println!("Invalid input ({}), please try again.", message);
+                }
+                result => break result,
+            }
+        }
+    };
+}
@@ -0,0 +1,42 @@
+//! A solver for crypto-arithmetic puzzles, where each letter represents a unique digit.
+//!
+//! # Examples
+//!
+//! ```
+//! use crypto_aritmatic::{Alphametic, Puzzle};
+//!
+//! let puzzle = Puzzle::new(vec!["SEND".to_string(), "MORE".to_string()], "MONEY".to_string());
+//! if let Some(solution) = puzzle.solve() {
+//!     for (ch, digit) in solution {
+//!         println!("{} = {}", ch, digit);
+//!     }
+//! }
+//! ```
+
+mod alphametic;
+mod division;
+mod expression;
+mod input;
+mod limit;
+mod multiplication;
+mod puzzle;
+mod render;
+mod search;
+mod solution;
+mod solver;
+mod stats;
+
+pub use alphametic::{Alphametic, Settings};
+pub use division::{is_valid_division, Division, DivisionStep};
+pub use expression::{Equation, Expr};
+pub use input::InputError;
+pub use limit::{CancelToken, Stop};
+pub use multiplication::{is_valid_product, Multiplication};
+pub use puzzle::{ParsePuzzleError, Puzzle, Sign, Term};
+pub use solution::{LetterOrder, Solution};
+pub use solver::{
+    check_uniqueness, count_solutions, is_valid_solution, is_valid_terms_solution, leading_letters,
+    solve_all, solve_crypto_arithmetic, word_to_number, word_to_number_in_base, Solutions,
+    SolveError, Uniqueness,
+};
+pub use stats::{Prunes, Stats};
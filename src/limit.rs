@@ -0,0 +1,122 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::stats::{Progress, Stats};
+
+/// How many nodes a search visits between two looks at its [`Interrupt`].
+pub(crate) const POLL_INTERVAL: u64 = 1024;
+
+/// A handle that stops a search from another thread.
+///
+/// Clones share the same flag, so a token can be handed to
+/// [`Solutions::with_cancel_token`](crate::Solutions::with_cancel_token) and kept
+/// to cancel the search later.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{Alphametic, CancelToken, Puzzle, Stop};
+///
+/// let token = CancelToken::new();
+/// let puzzle: Puzzle = "SEND + MORE = MONEY".parse().unwrap();
+/// let mut solutions = puzzle.solutions().with_cancel_token(token.clone());
+/// token.cancel();
+/// assert_eq!(solutions.next(), None);
+/// assert_eq!(solutions.stopped(), Some(Stop::Cancelled));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Creates a token that has not been cancelled.
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    /// Stops every search holding this token, at its next look at the token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` once [`CancelToken::cancel`] has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Why a search ended before exploring its whole search space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stop {
+    /// The time budget set with [`Solutions::with_timeout`](crate::Solutions::with_timeout) ran out.
+    TimedOut,
+    /// The search's [`CancelToken`] was cancelled.
+    Cancelled,
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stop::TimedOut => write!(f, "timed out"),
+            Stop::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// Everything a search looks at every [`POLL_INTERVAL`] nodes: what may end
+/// it early, and where to report its progress.
+#[derive(Clone, Default)]
+pub(crate) struct Interrupt {
+    pub(crate) deadline: Option<Instant>,
+    pub(crate) tokens: Vec<CancelToken>,
+    /// Set when a split search no longer needs this part; not reported as a [`Stop`].
+    pub(crate) halt: Option<Arc<AtomicBool>>,
+    pub(crate) progress: Option<Arc<Progress>>,
+    /// The statistics of the search when its progress was last reported.
+    reported: Stats,
+    /// Node count at which to look again.
+    next_poll: u64,
+}
+
+impl Interrupt {
+    /// Returns `true` if a search that has reached `stats` should end, looking at
+    /// the clock and the flags (and reporting progress) only once every
+    /// [`POLL_INTERVAL`] nodes.
+    pub(crate) fn poll(&mut self, stats: &Stats) -> bool {
+        if stats.nodes < self.next_poll {
+            return false;
+        }
+        self.next_poll = stats.nodes + POLL_INTERVAL;
+        if let Some(progress) = &self.progress {
+            progress.report(&stats.since(&self.reported));
+            self.reported = stats.clone();
+        }
+        self.is_set()
+    }
+
+    /// Returns `true` if the search should end now.
+    fn is_set(&self) -> bool {
+        self.reason().is_some()
+            || self
+                .halt
+                .as_ref()
+                .is_some_and(|halt| halt.load(Ordering::Relaxed))
+    }
+
+    /// Returns why the search should end, unless only its part of a split search was halted.
+    pub(crate) fn reason(&self) -> Option<Stop> {
+        if self.tokens.iter().any(CancelToken::is_cancelled) {
+            Some(Stop::Cancelled)
+        } else if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Some(Stop::TimedOut)
+        } else {
+            None
+        }
+    }
+}
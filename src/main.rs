@@ -1,188 +1,1032 @@
-use itertools::Itertools;
-use std::process::Command;
-use std::collections::HashSet;
+use crypto_aritmatic::{
+    try_input, Alphametic, Division, Equation, InputError, LetterOrder, Multiplication,
+    ParsePuzzleError, Prunes, Puzzle, Solution, Solutions, SolveError, Stats, Stop, Uniqueness,
+};
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
 
-/// A macro to prompt for user input with an optional message.
+/// The help text printed for `--help` and after an invalid argument.
+const USAGE: &str = "\
+Usage: cryptoaritmatic [solve EQUATION | batch FILE] [OPTIONS]
+
+Solves a crypto-arithmetic puzzle such as \"SEND + MORE = MONEY\". Without an
+equation, the words and the result are prompted for interactively. With
+`batch`, every line of FILE is solved as a puzzle (text after `#` is a comment)
+and a summary table is printed.
+
+Options:
+  -b, --base N               Numeric base of the puzzle, from 2 to 36 (default 10)
+  -a, --all                  Print every solution
+      --count                Print only the number of solutions
+      --unique               Check that the solution is unique
+  -z, --allow-leading-zeros  Let multi-letter words start with zero
+  -o, --order ORDER          Order of the letters in a solution: appearance
+                             (default), alphabetical or significance
+  -j, --threads N            Search with N threads, or one per core for 0 (default 1)
+  -t, --timeout SECONDS      Stop each search after SECONDS and report how far it got
+      --stats                Print the statistics of each search, and its progress
+                             every second on a terminal
+  -f, --format FORMAT        Output format: text (default), compact or json
+      --color                Colour each letter and its digits in the solved layout
+      --no-clear             Keep the screen when prompting interactively
+  -h, --help                 Print this help";
+
+/// How solutions are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// The letters of the solution followed by one `letter = digit` line each.
+    Text,
+    /// One line per solution, such as `S=9 E=5 N=6`.
+    Compact,
+    /// One JSON object per puzzle; see [`json_report`] for the schema.
+    Json,
+}
+
+/// The options given on the command line.
+#[derive(Debug)]
+struct Options {
+    /// The equation to solve, or `None` to prompt for the words instead.
+    equation: Option<String>,
+    /// A file of puzzles to solve, one per line.
+    batch: Option<String>,
+    base: u32,
+    all: bool,
+    count: bool,
+    unique: bool,
+    allow_leading_zeros: bool,
+    /// The order in which the letters of a solution are printed.
+    order: LetterOrder,
+    /// The number of threads to search with; 0 uses one per core.
+    threads: usize,
+    /// The time budget of each search, if any.
+    timeout: Option<Duration>,
+    /// Whether to print the statistics of each search.
+    stats: bool,
+    format: Format,
+    /// Whether to colour the letters of a solved sum.
+    color: bool,
+    /// Whether to clear the screen before prompting.
+    clear: bool,
+    help: bool,
+}
+
+/// Parses the command-line arguments, without the program name.
 ///
-/// # Examples
+/// # Returns
 ///
-/// ```
-/// let name: String = input!("Enter your name: ");
-/// println!("Hello, {}!", name);
-/// ```
-#[macro_export]
-macro_rules! input {
-    ($input_type:ty) => {
-        input!($input_type, "");
+/// The parsed options, or a message describing the first invalid argument.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        equation: None,
+        batch: None,
+        base: 10,
+        all: false,
+        count: false,
+        unique: false,
+        allow_leading_zeros: false,
+        order: LetterOrder::FirstAppearance,
+        threads: 1,
+        timeout: None,
+        stats: false,
+        format: Format::Text,
+        color: false,
+        clear: true,
+        help: false,
     };
-    () => {
-        input!(String, "");
-    };
-    ($msg:expr) => {
-        input!(String, $msg)
-    };
-    ($input_type:ty, $msg:expr) => {{
-        use std::io::{self, Write};
-        print!("{}", $msg);
-        io::stdout().flush().unwrap();
-        let mut user_input = String::new();
-        std::io::stdin()
-            .read_line(&mut user_input)
-            .expect("Failed to read input");
-        let trimmed_input = user_input.trim().to_string();
-        match trimmed_input.parse::<$input_type>() {
-            Ok(r) => r,
-            Err(error) => panic!("Cannot parse: {:?}", error),
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "solve" if options.equation.is_none() && options.batch.is_none() => {
+                let equation: String = args.next().ok_or("missing equation after 'solve'")?;
+                options.equation = Some(equation);
+            }
+            "batch" if options.equation.is_none() && options.batch.is_none() => {
+                let path: String = args.next().ok_or("missing file after 'batch'")?;
+                options.batch = Some(path);
+            }
+            "-a" | "--all" => options.all = true,
+            "--count" => options.count = true,
+            "--unique" => options.unique = true,
+            "-z" | "--allow-leading-zeros" => options.allow_leading_zeros = true,
+            "-b" | "--base" => {
+                let value: String = args.next().ok_or("missing value for --base")?;
+                options.base = match value.parse::<u32>() {
+                    Ok(base) if (2..=36).contains(&base) => base,
+                    _ => return Err(format!("invalid base {:?}, expected 2 to 36", value)),
+                };
+            }
+            "-o" | "--order" => {
+                options.order = match args.next().as_deref() {
+                    Some("appearance") => LetterOrder::FirstAppearance,
+                    Some("alphabetical") => LetterOrder::Alphabetical,
+                    Some("significance") => LetterOrder::MostSignificantFirst,
+                    Some(other) => return Err(format!("unknown letter order {:?}", other)),
+                    None => return Err("missing value for --order".to_string()),
+                };
+            }
+            "-j" | "--threads" => {
+                let value: String = args.next().ok_or("missing value for --threads")?;
+                options.threads = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid thread count {:?}", value))?;
+            }
+            "-t" | "--timeout" => {
+                let value: String = args.next().ok_or("missing value for --timeout")?;
+                let seconds: Option<Duration> = value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok());
+                options.timeout = Some(seconds.ok_or(format!("invalid timeout {:?}", value))?);
+            }
+            "-f" | "--format" => {
+                options.format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("compact") => Format::Compact,
+                    Some("json") => Format::Json,
+                    Some(other) => return Err(format!("unknown format {:?}", other)),
+                    None => return Err("missing value for --format".to_string()),
+                };
+            }
+            "--stats" => options.stats = true,
+            "--color" => options.color = true,
+            "--no-clear" => options.clear = false,
+            "-h" | "--help" => options.help = true,
+            _ => return Err(format!("unexpected argument {:?}", arg)),
         }
-    }};
+    }
+    Ok(options)
 }
 
-/// Converts a word to its numerical value based on the given character-to-digit mapping.
-///
-/// # Parameters
-///
-/// - `word`: A string slice representing the word to convert.
-/// - `mapping`: A slice of tuples, each containing a character and its corresponding digit.
+/// Prompts the user for input and returns the words and result as a tuple.
 ///
 /// # Returns
 ///
-/// The numerical value of the word as a `u32`.
+/// A tuple containing the words to add, in the order entered, and the result word,
+/// or the error that stopped the prompts (such as a closed stdin).
 ///
 /// # Examples
 ///
 /// ```
-/// let mapping = [('A', 1), ('B', 2), ('C', 3)];
-/// let result = word_to_number("ABC", &mapping);
-/// assert_eq!(result, 123);
+/// let (words, result) = inputs()?;
+/// println!("Words: {:?}, Result: {}", words, result);
 /// ```
-fn word_to_number(word: &str, mapping: &[(char, u32)]) -> u32 {
-    let mut number: u32 = 0;
-    for c in word.chars() {
-        let digit: u32 = mapping.iter().find(|&&(ch, _)| ch == c).unwrap().1;
-        number = number * 10 + digit;
+fn inputs() -> Result<(Vec<String>, String), InputError> {
+    let input1: String = try_input!("Words to Add, Separated with Whitespace or '+'? ")?;
+    let words: Vec<String> = input1
+        .split(|c: char| c.is_whitespace() || c == '+')
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect();
+    let result: String = try_input!("Result String? ")?;
+    Ok((words, result))
+}
+
+/// A puzzle ready to be solved, whatever its kind.
+struct Loaded {
+    /// The puzzle written back in normalized form.
+    equation: String,
+    /// Every word of the puzzle, from left to right.
+    words: Vec<String>,
+    solutions: Solutions,
+    /// The puzzle itself if it is a sum of words, which can be laid out in columns.
+    sum: Option<Puzzle>,
+}
+
+/// Why a puzzle given on the command line or in a batch cannot be solved.
+#[derive(Debug)]
+enum LoadError {
+    /// The equation could not be parsed.
+    Parse(ParsePuzzleError),
+    /// The puzzle is not well-formed, such as having more letters than digits.
+    Invalid(SolveError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Parse(error) => write!(f, "{}", error),
+            LoadError::Invalid(error) => write!(f, "{}", error),
+        }
     }
-    number
 }
 
-/// Checks if the current mapping satisfies the puzzle.
-///
-/// # Parameters
-///
-/// - `words`: A vector of strings representing the words.
-/// - `result`: A string representing the result word.
-/// - `mapping`: A slice of tuples, each containing a character and its corresponding digit.
-///
-/// # Returns
-///
-/// `true` if the solution is valid, otherwise `false`.
-///
-/// # Examples
-///
-/// ```
-/// let words = vec!["SEND".to_string(), "MORE".to_string()];
-/// let result = "MONEY".to_string();
-/// let mapping = [('S', 9), ('E', 5), ('N', 6), ('D', 7), ('M', 1), ('O', 0), ('R', 8), ('Y', 2)];
-/// assert!(is_valid_solution(&words, &result, &mapping));
-/// ```
-fn is_valid_solution(words: &Vec<String>, result: &String, mapping: &[(char, u32)]) -> bool {
-    let words_sum: u32 = words.iter().map(|word| word_to_number(word, mapping)).sum();
-    let result_value: u32 = word_to_number(result, mapping);
-    words_sum == result_value
+/// Applies the `--timeout` of `options`, if any, to a search, and with `--stats`
+/// prints its progress to stderr every second when stderr is a terminal.
+fn bounded(solutions: Solutions, options: &Options) -> Solutions {
+    let solutions: Solutions = match options.timeout {
+        Some(timeout) => solutions.with_timeout(timeout),
+        None => solutions,
+    };
+    if options.stats && io::stderr().is_terminal() {
+        solutions.with_progress(Duration::from_secs(1), |stats: &Stats| {
+            eprintln!("Searching: {}", stats);
+        })
+    } else {
+        solutions
+    }
+}
+
+/// Applies the base, leading-zero rule and letter order of `options` to a
+/// puzzle, and checks that it is well-formed.
+fn configure(
+    puzzle: impl Alphametic + fmt::Display,
+    options: &Options,
+) -> Result<Loaded, LoadError> {
+    let puzzle = puzzle
+        .with_base(options.base)
+        .allow_leading_zeros(options.allow_leading_zeros)
+        .with_letter_order(options.order);
+    puzzle.validate().map_err(LoadError::Invalid)?;
+    Ok(Loaded {
+        equation: puzzle.to_string(),
+        words: puzzle.words().into_iter().map(String::from).collect(),
+        solutions: bounded(puzzle.solutions(), options),
+        sum: None,
+    })
 }
 
-/// Solves the crypto-arithmetic puzzle for the given words and result.
+/// Parses `equation` as the most specific kind of puzzle that accepts it:
+/// a sum or difference of words, a multiplication, a long division, and
+/// finally a general equation, and checks that it is well-formed.
+fn load(equation: &str, options: &Options) -> Result<Loaded, LoadError> {
+    if let Ok(puzzle) = equation.parse::<Puzzle>() {
+        return Ok(Loaded {
+            sum: Some(puzzle.clone()),
+            ..configure(puzzle, options)?
+        });
+    }
+    if let Ok(puzzle) = equation.parse::<Multiplication>() {
+        return configure(puzzle, options);
+    }
+    if let Ok(puzzle) = equation.parse::<Division>() {
+        return configure(puzzle, options);
+    }
+    let puzzle: Equation = equation.parse().map_err(LoadError::Parse)?;
+    configure(puzzle, options)
+}
+
+/// Clears the terminal screen with ANSI escape codes.
 ///
-/// # Parameters
+/// Does nothing when stdout is not a terminal, so piped or redirected output
+/// stays free of escape codes.
+fn cls() {
+    let mut stdout = io::stdout();
+    if stdout.is_terminal() {
+        // Erase the whole screen, then move the cursor to the top-left corner.
+        let _ = write!(stdout, "\x1b[2J\x1b[H");
+        let _ = stdout.flush();
+    }
+}
+
+/// Prints a solution as text, or on one line for [`Format::Compact`].
 ///
-/// - `words`: A vector of strings representing the words.
-/// - `result`: A string representing the result word.
+/// In text, a sum is also laid out in columns with the digits substituted.
+fn print_solution(solution: &Solution, sum: Option<&Puzzle>, options: &Options) {
+    if options.format == Format::Compact {
+        println!("{}", compact(solution));
+        return;
+    }
+    println!("Solution found: {}", solution.letters());
+    for (ch, digit) in solution {
+        println!("{} = {}", ch, digit);
+    }
+    if let Some(sum) = sum {
+        println!();
+        println!("{}", sum.render(solution, options.color));
+        println!();
+    }
+}
+
+/// Writes a solution on one line, such as `S=9 E=5 N=6`.
+fn compact(solution: &Solution) -> String {
+    let pairs: Vec<String> = solution
+        .into_iter()
+        .map(|(ch, digit)| format!("{}={}", ch, digit))
+        .collect();
+    pairs.join(" ")
+}
+
+/// Writes the number of branches cut by every rule together, followed by the
+/// rules that cut any, such as `120 (digit taken 100, column 20)`.
+fn prunes_summary(prunes: &Prunes) -> String {
+    let rules: Vec<String> = prunes
+        .rules()
+        .iter()
+        .filter(|&&(_, count)| count > 0)
+        .map(|(rule, count)| format!("{} {}", rule, count))
+        .collect();
+    if rules.is_empty() {
+        prunes.total().to_string()
+    } else {
+        format!("{} ({})", prunes.total(), rules.join(", "))
+    }
+}
+
+/// Prints the statistics of a search, one counter per line.
+fn print_stats(stats: &Stats) {
+    println!("Nodes visited: {}", stats.nodes);
+    println!("Backtracks: {}", stats.backtracks);
+    println!("Max depth: {}", stats.max_depth);
+    println!("Prunes: {}", prunes_summary(&stats.prunes));
+    println!("Search time: {:.2?}", stats.elapsed);
+}
+
+/// Quotes `text` as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Writes a solution as a JSON object holding its `mapping` from letters to
+/// digits, in alphabetical order, and the `values` of `words`.
 ///
-/// # Returns
+/// Values are written as strings of decimal digits, since words of 16 or more
+/// digits go past the integers a JSON number holds exactly; a value too large
+/// for a `u128` is `null`.
+fn json_solution(solution: &Solution, words: &[String]) -> String {
+    let mut mapping: Vec<(char, u32)> = solution.mapping().to_vec();
+    mapping.sort();
+    let mapping: Vec<String> = mapping
+        .iter()
+        .map(|(ch, digit)| format!("{}:{}", json_string(&ch.to_string()), digit))
+        .collect();
+    let values: Vec<String> = words
+        .iter()
+        .map(|word| {
+            let value: String = match solution.try_value(word) {
+                Ok(value) => json_string(&value.to_string()),
+                Err(_) => "null".to_string(),
+            };
+            format!("{}:{}", json_string(word), value)
+        })
+        .collect();
+    format!(
+        "{{\"mapping\":{{{}}},\"values\":{{{}}}}}",
+        mapping.join(","),
+        values.join(",")
+    )
+}
+
+/// Writes the statistics of a search as a JSON object, with one key per rule
+/// under `prunes`.
+fn json_stats(stats: &Stats) -> String {
+    let prunes: Vec<String> = stats
+        .prunes
+        .rules()
+        .iter()
+        .map(|(rule, count)| format!("{}:{}", json_string(&rule.replace(' ', "_")), count))
+        .collect();
+    format!(
+        "{{\"nodes\":{},\"backtracks\":{},\"max_depth\":{},\"prunes\":{{{}}},\"elapsed_ms\":{:.3}}}",
+        stats.nodes,
+        stats.backtracks,
+        stats.max_depth,
+        prunes.join(","),
+        stats.elapsed.as_secs_f64() * 1000.0
+    )
+}
+
+/// Writes the JSON object reported for one puzzle.
 ///
-/// An optional vector of tuples, each containing a character and its corresponding digit.
+/// Every object has the same keys, so the schema is stable:
 ///
-/// # Examples
+/// - `puzzle`: the puzzle in normalized form (or as given, if it is invalid);
+/// - `base`: its numeric base;
+/// - `letters`: its distinct letters, in alphabetical order;
+/// - `solutions`: the solutions found, each with a `mapping` object from letter
+///   to digit and a `values` object from every word to its value as a string
+///   of decimal digits (or `null` if it does not fit in a `u128`);
+/// - `count`: the total number of solutions, or `null` if the search stopped
+///   before it was known;
+/// - `unique`: with `--unique`, whether there is exactly one solution, otherwise `null`;
+/// - `nodes`: the number of nodes of the search tree visited;
+/// - `stopped`: `"timed out"` if the `--timeout` ran out, which leaves `count`
+///   and `unique` unknown, otherwise `null`;
+/// - `stats`: with `--stats`, an object with the `nodes`, `backtracks`,
+///   `max_depth`, `prunes` by rule and `elapsed_ms` of the search, otherwise `null`;
+/// - `elapsed_ms`: the time spent on the puzzle, in milliseconds;
+/// - `error`: why the puzzle could not be parsed, or why it is not well-formed
+///   (such as having more letters than digits), or `null`.
 ///
-/// ```
-/// let words = vec!["SEND".to_string(), "MORE".to_string()];
-/// let result = "MONEY".to_string();
-/// if let Some(solution) = solve_crypto_arithmetic(words, result) {
-///     for (ch, digit) in solution {
-///         println!("{} = {}", ch, digit);
-///     }
-/// } else {
-///     println!("No solution found.");
-/// }
-/// ```
-fn solve_crypto_arithmetic(words: Vec<String>, result: String) -> Option<Vec<(char, u32)>> {
-    let mut letters: HashSet<char> = HashSet::new();
-    for word in &words {
-        for c in word.chars() {
-            letters.insert(c);
+/// `loaded` is the puzzle, or the text of an invalid line with its parse error.
+fn json_report(
+    loaded: Result<Loaded, (&str, LoadError)>,
+    options: &Options,
+    start: Instant,
+) -> String {
+    let (puzzle, error, words, solutions, count, unique, stats, stopped) = match loaded {
+        Err((line, error)) => (
+            line.to_string(),
+            Some(error.to_string()),
+            Vec::new(),
+            Vec::new(),
+            None,
+            None,
+            Stats::default(),
+            None,
+        ),
+        Ok(Loaded {
+            equation,
+            words,
+            solutions: mut search,
+            ..
+        }) => {
+            let (solutions, count, unique): (Vec<Solution>, Option<usize>, Option<bool>) =
+                if options.unique {
+                    match search.uniqueness_parallel(options.threads) {
+                        Uniqueness::NoSolution => (Vec::new(), Some(0), Some(false)),
+                        Uniqueness::Unique(solution) => (vec![solution], Some(1), Some(true)),
+                        Uniqueness::Multiple(first, second) => {
+                            (vec![first, second], None, Some(false))
+                        }
+                    }
+                } else if options.count {
+                    (
+                        Vec::new(),
+                        Some(search.count_parallel(options.threads)),
+                        None,
+                    )
+                } else if options.all {
+                    let all: Vec<Solution> = search.take_parallel(usize::MAX, options.threads);
+                    let count: usize = all.len();
+                    (all, Some(count), None)
+                } else {
+                    let first: Vec<Solution> = search.take_parallel(1, options.threads);
+                    let count: Option<usize> = if first.is_empty() { Some(0) } else { None };
+                    (first, count, None)
+                };
+            let stopped: Option<Stop> = search.stopped();
+            let (count, unique) = match stopped {
+                Some(_) => (None, None),
+                None => (count, unique),
+            };
+            (
+                equation,
+                None,
+                words,
+                solutions,
+                count,
+                unique,
+                search.stats(),
+                stopped,
+            )
+        }
+    };
+    let elapsed_ms: f64 = start.elapsed().as_secs_f64() * 1000.0;
+
+    let mut letters: Vec<char> = words.iter().flat_map(|word| word.chars()).collect();
+    letters.sort();
+    letters.dedup();
+    let mut unique_words: Vec<String> = Vec::new();
+    for word in words {
+        if !unique_words.contains(&word) {
+            unique_words.push(word);
         }
     }
-    for c in result.chars() {
-        letters.insert(c);
+    let solutions: Vec<String> = solutions
+        .iter()
+        .map(|solution| json_solution(solution, &unique_words))
+        .collect();
+    let null = || "null".to_string();
+    format!(
+        "{{\"puzzle\":{},\"base\":{},\"letters\":{},\"solutions\":[{}],\"count\":{},\"unique\":{},\"nodes\":{},\"stopped\":{},\"stats\":{},\"elapsed_ms\":{:.3},\"error\":{}}}",
+        json_string(&puzzle),
+        options.base,
+        json_string(&letters.into_iter().collect::<String>()),
+        solutions.join(","),
+        count.map_or_else(null, |count| count.to_string()),
+        unique.map_or_else(null, |unique| unique.to_string()),
+        stats.nodes,
+        stopped.map_or_else(null, |stop| json_string(&stop.to_string())),
+        if options.stats {
+            json_stats(&stats)
+        } else {
+            null()
+        },
+        elapsed_ms,
+        error.as_deref().map_or_else(null, json_string),
+    )
+}
+
+/// Answers the question selected by `options` about a puzzle's solutions.
+fn report(mut solutions: Solutions, sum: Option<&Puzzle>, options: &Options) {
+    // A search that stopped early only knows about the solutions it found, so
+    // it never claims there are none, or that one is unique.
+    if options.unique {
+        let uniqueness: Uniqueness = solutions.uniqueness_parallel(options.threads);
+        let stopped: bool = solutions.stopped().is_some();
+        match uniqueness {
+            Uniqueness::NoSolution if stopped => {}
+            Uniqueness::Unique(solution) if stopped => {
+                println!("Uniqueness: at least one solution");
+                print_solution(&solution, sum, options);
+            }
+            uniqueness => {
+                println!("Uniqueness: {}", uniqueness);
+                match uniqueness {
+                    Uniqueness::NoSolution => {}
+                    Uniqueness::Unique(solution) => print_solution(&solution, sum, options),
+                    Uniqueness::Multiple(first, second) => {
+                        print_solution(&first, sum, options);
+                        print_solution(&second, sum, options);
+                    }
+                }
+            }
+        }
+    } else if options.count {
+        let count: usize = solutions.count_parallel(options.threads);
+        if solutions.stopped().is_none() {
+            println!("Solutions: {}", count);
+        } else if count > 0 {
+            println!("Solutions: at least {}", count);
+        }
+    } else if options.all {
+        // A single thread prints each solution as soon as it is found; a split
+        // search only has its solutions in order once every part has finished.
+        let mut found: usize = 0;
+        if options.threads == 1 {
+            for solution in solutions.by_ref() {
+                print_solution(&solution, sum, options);
+                found += 1;
+            }
+        } else {
+            for solution in solutions.take_parallel(usize::MAX, options.threads) {
+                print_solution(&solution, sum, options);
+                found += 1;
+            }
+        }
+        if solutions.stopped().is_none() {
+            if found == 0 {
+                println!("No solution found.");
+            }
+        } else if found > 0 {
+            println!("Solutions: at least {}", found);
+        }
+    } else {
+        match solutions.take_parallel(1, options.threads).pop() {
+            Some(solution) => print_solution(&solution, sum, options),
+            None if solutions.stopped().is_some() => {}
+            None => println!("No solution found."),
+        }
+    }
+    if let Some(stop) = solutions.stopped() {
+        println!(
+            "Search {} after {} nodes; the results are incomplete.",
+            stop,
+            solutions.nodes()
+        );
     }
-    let letters: Vec<char> = letters.into_iter().collect();
-    if letters.len() > 10 {
-        return None;
+    if options.stats {
+        println!();
+        print_stats(&solutions.stats());
     }
+}
 
-    let digits: Vec<u32> = (0..10).collect();
-    let permutations: itertools::Permutations<std::iter::Cloned<std::slice::Iter<u32>>> = digits.iter().cloned().permutations(letters.len());
+/// Answers the question selected by `options` for one puzzle of a batch.
+///
+/// # Returns
+///
+/// The status to show, the solution to show (if any), and whether the puzzle
+/// has at least one solution, or `None` if the search stopped before finding one.
+fn summarize(
+    solutions: &mut Solutions,
+    options: &Options,
+) -> (String, Option<Solution>, Option<bool>) {
+    let (status, solution, found) = if options.unique {
+        match solutions.uniqueness_parallel(options.threads) {
+            Uniqueness::NoSolution => ("no solution".to_string(), None, false),
+            Uniqueness::Unique(solution) => ("unique".to_string(), Some(solution), true),
+            Uniqueness::Multiple(first, _) => ("multiple".to_string(), Some(first), true),
+        }
+    } else if options.count {
+        let count: usize = solutions.count_parallel(options.threads);
+        (format!("{} solutions", count), None, count > 0)
+    } else {
+        match solutions.take_parallel(1, options.threads).pop() {
+            Some(solution) => ("solved".to_string(), Some(solution), true),
+            None => ("no solution".to_string(), None, false),
+        }
+    };
+    match solutions.stopped() {
+        Some(stop) => {
+            let stop: String = format!("{} after {} nodes", stop, solutions.nodes());
+            if found {
+                (format!("{}, {}", status, stop), solution, Some(true))
+            } else {
+                (stop, solution, None)
+            }
+        }
+        None => (status, solution, Some(found)),
+    }
+}
 
-    for perm in permutations {
-        let mapping: Vec<(char, u32)> = letters.iter().cloned().zip(perm).collect();
-        if is_valid_solution(&words, &result, &mapping) {
-            return Some(mapping);
+/// Prints `rows` under `header`, padding every column to its widest cell.
+fn print_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) {
+    let mut widths: [usize; N] = header.map(|title| title.chars().count());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
         }
     }
-    None
+    let print_row = |cells: [&str; N]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    print_row(header);
+    print_row(
+        widths
+            .map(|width| "-".repeat(width))
+            .each_ref()
+            .map(String::as_str),
+    );
+    for row in rows {
+        print_row(row.each_ref().map(String::as_str));
+    }
 }
 
-/// Prompts the user for input and returns the words and result as a tuple.
-///
-/// # Returns
+/// Returns the puzzle on a line of a batch file, without its `#` comment and
+/// the whitespace around it.
+fn puzzle_text(line: &str) -> &str {
+    line.split('#').next().unwrap_or_default().trim()
+}
+
+/// Solves every puzzle in the file at `path` and prints a summary table with
+/// the status, solution and solving time of each, or a JSON array with one
+/// object per puzzle for [`Format::Json`]. With `--stats`, a second table
+/// lists the statistics of each search.
 ///
-/// A tuple containing a vector of two words and the result word.
+/// Each line holds one puzzle in any form accepted by `solve`; blank lines and
+/// everything after a `#` are ignored.
 ///
-/// # Examples
+/// # Returns
 ///
-/// ```
-/// let (words, result) = inputs();
-/// println!("Words: {:?}, Result: {}", words, result);
-/// ```
-fn inputs() -> (Vec<String>, String) {
-    let input1: String = input!("Two Words as Input, Separated with Whitespace? ");
-    let words: Vec<String> = input1.split_whitespace().map(String::from).collect();
-    let result: String = input!("Result String? ");
-    (words, result)
-}
+/// `false` if the file could not be read or any of its puzzles is invalid.
+fn run_batch(path: &str, options: &Options) -> bool {
+    let contents: String = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return false;
+        }
+    };
 
-/// Clears the terminal screen. Only works on Windows.
-fn cls() {
-    Command::new("cmd")
-        .args(&["/C", "cls"])
-        .status()
-        .unwrap();
+    let mut rows: Vec<[String; 5]> = Vec::new();
+    let mut stats_rows: Vec<[String; 5]> = Vec::new();
+    let mut objects: Vec<String> = Vec::new();
+    let (mut solved, mut unsolved, mut stopped, mut invalid) = (0usize, 0usize, 0usize, 0usize);
+    for (number, line) in contents.lines().enumerate() {
+        let line: &str = puzzle_text(line);
+        if line.is_empty() {
+            continue;
+        }
+        let start: Instant = Instant::now();
+        if options.format == Format::Json {
+            let loaded = load(line, options).map_err(|error| (line, error));
+            invalid += usize::from(loaded.is_err());
+            objects.push(json_report(loaded, options, start));
+            continue;
+        }
+        let (puzzle, status, solution) = match load(line, options) {
+            Ok(mut loaded) => {
+                let (status, solution, found) = summarize(&mut loaded.solutions, options);
+                let stats: Stats = loaded.solutions.stats();
+                stats_rows.push([
+                    (number + 1).to_string(),
+                    stats.nodes.to_string(),
+                    stats.backtracks.to_string(),
+                    stats.max_depth.to_string(),
+                    prunes_summary(&stats.prunes),
+                ]);
+                match found {
+                    Some(true) => solved += 1,
+                    Some(false) => unsolved += 1,
+                    None => stopped += 1,
+                }
+                (loaded.equation, status, solution.as_ref().map(compact))
+            }
+            Err(error) => {
+                invalid += 1;
+                (line.to_string(), format!("error: {}", error), None)
+            }
+        };
+        rows.push([
+            (number + 1).to_string(),
+            puzzle,
+            status,
+            solution.unwrap_or_default(),
+            format!("{:.2?}", start.elapsed()),
+        ]);
+    }
+
+    if options.format == Format::Json {
+        println!("[\n{}\n]", objects.join(",\n"));
+        return invalid == 0;
+    }
+    print_table(["Line", "Puzzle", "Status", "Solution", "Time"], &rows);
+    println!();
+    if options.stats {
+        print_table(
+            ["Line", "Nodes", "Backtracks", "Max depth", "Prunes"],
+            &stats_rows,
+        );
+        println!();
+    }
+    let stopped: String = match stopped {
+        0 => String::new(),
+        stopped => format!(", {} timed out", stopped),
+    };
+    println!(
+        "{} puzzles: {} solved, {} without solution{}, {} invalid",
+        rows.len(),
+        solved,
+        unsolved,
+        stopped,
+        invalid
+    );
+    invalid == 0
 }
 
 /// The main function to execute the program.
+///
+/// Run `cryptoaritmatic solve "SEND + MORE = MONEY"` to solve a puzzle given on
+/// the command line, or without an equation to be prompted for the words. Pass
+/// `--all` to list every solution, `--count` to print only how many there are,
+/// or `--unique` to check that the puzzle has exactly one solution; see `--help`
+/// for the remaining options.
 fn main() {
-    cls();
-    let (words, result) = inputs();
-    // let (words, result) = (vec!["Send".to_string() , "more".to_string()] , "monry".to_string());
+    let options: Options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+    if let Some(path) = &options.batch {
+        if !run_batch(path, &options) {
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    println!("{} + {} = {}", words[0], words[1], result);
-    match solve_crypto_arithmetic(words, result) {
-        Some(mapping) => {
-            let extracted_string: String = mapping.iter().map(|&(ch, _)| ch).collect();
-            println!("Solution found: {}", extracted_string);
-            for (ch, digit) in mapping {
-                println!("{} = {}", ch, digit);
+    let start: Instant = Instant::now();
+    let loaded: Loaded = match &options.equation {
+        Some(equation) => match load(equation, &options) {
+            Ok(loaded) => loaded,
+            Err(error) if options.format == Format::Json => {
+                println!("{}", json_report(Err((equation, error)), &options, start));
+                std::process::exit(2);
+            }
+            Err(error) => {
+                eprintln!("error: {}", error);
+                std::process::exit(2);
+            }
+        },
+        None => {
+            if options.clear {
+                cls();
+            }
+            let (words, result) = match inputs() {
+                Ok(inputs) => inputs,
+                Err(error) => {
+                    eprintln!("error: {}", error);
+                    std::process::exit(1);
+                }
+            };
+            // let (words, result) = (vec!["Send".to_string() , "more".to_string()] , "monry".to_string());
+            if words.is_empty() || result.is_empty() {
+                println!("At least one word and a result are required.");
+                return;
+            }
+            let puzzle: Puzzle = Puzzle::new(words, result)
+                .with_base(options.base)
+                .allow_leading_zeros(options.allow_leading_zeros)
+                .with_letter_order(options.order);
+            if let Err(error) = puzzle.validate() {
+                eprintln!("error: {}", error);
+                std::process::exit(2);
+            }
+            Loaded {
+                equation: puzzle.to_string(),
+                words: puzzle.words().into_iter().map(String::from).collect(),
+                solutions: bounded(puzzle.solutions(), &options),
+                sum: Some(puzzle),
             }
         }
-        None => println!("No solution found."),
+    };
+    if options.format == Format::Json {
+        println!("{}", json_report(Ok(loaded), &options, start));
+        return;
+    }
+    if options.format == Format::Text {
+        println!("{}", loaded.equation);
+    }
+    report(loaded.solutions, loaded.sum.as_ref(), &options);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses `args` as they would be given after the program name.
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_args_defaults() {
+        let options: Options = parse(&[]).unwrap();
+        assert_eq!(options.equation, None);
+        assert_eq!(options.batch, None);
+        assert_eq!(options.base, 10);
+        assert!(!options.all && !options.count && !options.unique);
+        assert!(!options.allow_leading_zeros);
+        assert_eq!(options.threads, 1);
+        assert_eq!(options.timeout, None);
+        assert_eq!(options.format, Format::Text);
+        assert!(options.clear);
+        assert!(!options.help);
+    }
+
+    #[test]
+    fn parse_args_flags() {
+        let options: Options = parse(&[
+            "solve",
+            "SEND + MORE = MONEY",
+            "-b",
+            "16",
+            "--all",
+            "-z",
+            "-o",
+            "alphabetical",
+            "-j",
+            "0",
+            "-t",
+            "0.5",
+            "--format",
+            "compact",
+            "--color",
+            "--no-clear",
+            "--stats",
+        ])
+        .unwrap();
+        assert_eq!(options.equation.as_deref(), Some("SEND + MORE = MONEY"));
+        assert_eq!(options.base, 16);
+        assert!(options.all && options.allow_leading_zeros);
+        assert_eq!(options.order, LetterOrder::Alphabetical);
+        assert_eq!(options.threads, 0);
+        assert_eq!(options.timeout, Some(Duration::from_millis(500)));
+        assert_eq!(options.format, Format::Compact);
+        assert!(options.color && options.stats && !options.clear);
+
+        let options: Options = parse(&["batch", "puzzles.txt", "--count", "--unique"]).unwrap();
+        assert_eq!(options.batch.as_deref(), Some("puzzles.txt"));
+        assert!(options.count && options.unique);
+        assert!(parse(&["-h"]).unwrap().help);
+    }
+
+    #[test]
+    fn parse_args_errors() {
+        assert_eq!(
+            parse(&["solve"]).unwrap_err(),
+            "missing equation after 'solve'"
+        );
+        assert_eq!(parse(&["batch"]).unwrap_err(), "missing file after 'batch'");
+        assert_eq!(
+            parse(&["solve", "A = B", "solve", "C = D"]).unwrap_err(),
+            "unexpected argument \"solve\""
+        );
+        assert_eq!(
+            parse(&["--base", "37"]).unwrap_err(),
+            "invalid base \"37\", expected 2 to 36"
+        );
+        assert_eq!(parse(&["-b"]).unwrap_err(), "missing value for --base");
+        assert_eq!(
+            parse(&["-o", "random"]).unwrap_err(),
+            "unknown letter order \"random\""
+        );
+        assert_eq!(
+            parse(&["-j", "-1"]).unwrap_err(),
+            "invalid thread count \"-1\""
+        );
+        assert_eq!(parse(&["-t", "-1"]).unwrap_err(), "invalid timeout \"-1\"");
+        assert_eq!(parse(&["-f", "xml"]).unwrap_err(), "unknown format \"xml\"");
+        assert_eq!(
+            parse(&["--verbose"]).unwrap_err(),
+            "unexpected argument \"--verbose\""
+        );
+    }
+
+    #[test]
+    fn puzzle_text_strips_comments() {
+        assert_eq!(
+            puzzle_text("  SEND + MORE = MONEY  "),
+            "SEND + MORE = MONEY"
+        );
+        assert_eq!(puzzle_text("A + B = C # easy"), "A + B = C");
+        assert_eq!(puzzle_text("# a comment"), "");
+        assert_eq!(puzzle_text("   "), "");
+    }
+
+    /// Summarizes `equation` as a line of a batch run with `args`.
+    fn summary(equation: &str, args: &[&str]) -> (String, Option<String>, Option<bool>) {
+        let options: Options = parse(args).unwrap();
+        let mut loaded: Loaded = load(equation, &options).unwrap();
+        let (status, solution, found) = summarize(&mut loaded.solutions, &options);
+        (status, solution.as_ref().map(compact), found)
+    }
+
+    #[test]
+    fn summarize_statuses() {
+        assert_eq!(
+            summary("SEND + MORE = MONEY", &[]),
+            (
+                "solved".to_string(),
+                Some("S=9 E=5 N=6 D=7 M=1 O=0 R=8 Y=2".to_string()),
+                Some(true)
+            )
+        );
+        assert_eq!(
+            summary("A + B = CDE", &[]),
+            ("no solution".to_string(), None, Some(false))
+        );
+        assert_eq!(summary("SEND + MORE = MONEY", &["--unique"]).0, "unique");
+        assert_eq!(summary("A + B = C", &["--unique"]).0, "multiple");
+        assert_eq!(
+            summary("A + B = CDE", &["--unique"]),
+            ("no solution".to_string(), None, Some(false))
+        );
+        assert_eq!(
+            summary("SEND + MORE = MONEY", &["-z", "--count"]),
+            ("25 solutions".to_string(), None, Some(true))
+        );
+        assert_eq!(
+            summary("A + B = CDE", &["--count"]),
+            ("0 solutions".to_string(), None, Some(false))
+        );
+    }
+
+    #[test]
+    fn json_string_escapes() {
+        assert_eq!(json_string("SEND"), "\"SEND\"");
+        assert_eq!(json_string("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(json_string("a\\b"), "\"a\\\\b\"");
+        assert_eq!(json_string("tab\there\n"), "\"tab\\u0009here\\u000a\"");
+        assert_eq!(json_string("ÄÖ"), "\"ÄÖ\"");
+    }
+
+    #[test]
+    fn json_solution_lists_every_word() {
+        let options: Options = parse(&[]).unwrap();
+        let mut loaded: Loaded = load("A + B = C", &options).unwrap();
+        let solution: Solution = loaded.solutions.next().unwrap();
+        let words: Vec<String> = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        assert_eq!(
+            json_solution(&solution, &words),
+            "{\"mapping\":{\"A\":1,\"B\":2,\"C\":3},\"values\":{\"A\":\"1\",\"B\":\"2\",\"C\":\"3\"}}"
+        );
+    }
+
+    #[test]
+    fn json_report_invalid_puzzle() {
+        let options: Options = parse(&["-f", "json"]).unwrap();
+        let error: LoadError = match load("ABCDEF + GHIJK = LMN", &options) {
+            Err(error) => error,
+            Ok(_) => panic!("a puzzle with 14 letters loaded"),
+        };
+        let report: String = json_report(
+            Err(("ABCDEF + GHIJK = LMN", error)),
+            &options,
+            Instant::now(),
+        );
+        assert!(report.starts_with("{\"puzzle\":\"ABCDEF + GHIJK = LMN\",\"base\":10,"));
+        assert!(report.contains("\"solutions\":[],\"count\":null,\"unique\":null,"));
+        assert!(
+            report.ends_with("\"error\":\"14 distinct letters, but base 10 only has 10 digits\"}")
+        );
+    }
+
+    #[test]
+    fn summarize_stopped_search() {
+        let (status, solution, found) = summary("SEND + MORE = MONEY", &["-t", "0"]);
+        assert!(status.starts_with("timed out after "), "{}", status);
+        assert_eq!((solution, found), (None, None));
     }
 }
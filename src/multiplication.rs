@@ -0,0 +1,334 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::alphametic::{Alphametic, Settings};
+use crate::puzzle::{parse_word, split_equation, ParsePuzzleError};
+use crate::search::{add_product_checks, column_order, letter_indices, BacktrackSolver};
+use crate::solver::{leading_letters, word_to_number_in_base, Engine, Solutions, SolveError};
+
+/// A multiplication puzzle such as `AB * CD = EFGH`.
+///
+/// The partial products of long multiplication can be given as extra rows, one
+/// per digit of the multiplier, starting from its least significant digit. Each
+/// row must then equal the multiplicand times that digit.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{Alphametic, Multiplication};
+///
+/// let puzzle: Multiplication = "ab * cd = efgh".parse().unwrap();
+/// assert_eq!(puzzle.to_string(), "AB * CD = EFGH");
+/// let solution = puzzle.solve().unwrap();
+/// assert_eq!(solution.value("AB") * solution.value("CD"), solution.value("EFGH"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Multiplication {
+    multiplicand: String,
+    multiplier: String,
+    partials: Vec<String>,
+    product: String,
+    settings: Settings,
+}
+
+impl Multiplication {
+    /// Creates a multiplication puzzle without partial products, following the leading-zero rule.
+    ///
+    /// # Parameters
+    ///
+    /// - `multiplicand`: The word that is multiplied.
+    /// - `multiplier`: The word it is multiplied by.
+    /// - `product`: The result word.
+    pub fn new(multiplicand: String, multiplier: String, product: String) -> Self {
+        Multiplication {
+            multiplicand,
+            multiplier,
+            partials: Vec::new(),
+            product,
+            settings: Settings::default(),
+        }
+    }
+
+    /// Sets the partial products of the long multiplication layout.
+    ///
+    /// There must be one row per digit of the multiplier, starting from its least
+    /// significant digit; otherwise [`Alphametic::validate`] reports
+    /// [`SolveError::PartialProducts`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Multiplication};
+    ///
+    /// let puzzle = Multiplication::new("AB".to_string(), "CD".to_string(), "EFGE".to_string());
+    /// assert_eq!(puzzle.count_solutions(), 10);
+    ///
+    /// // 47 * 23 = 1081, with partial products 141 and 94.
+    /// let puzzle = puzzle.with_partials(vec!["EAE".to_string(), "HA".to_string()]);
+    /// assert_eq!(puzzle.count_solutions(), 1);
+    /// assert_eq!(puzzle.solve().unwrap().value("AB"), 47);
+    /// ```
+    pub fn with_partials(mut self, partials: Vec<String>) -> Self {
+        self.partials = partials;
+        self
+    }
+
+    /// Returns the word that is multiplied.
+    pub fn multiplicand(&self) -> &str {
+        &self.multiplicand
+    }
+
+    /// Returns the word the multiplicand is multiplied by.
+    pub fn multiplier(&self) -> &str {
+        &self.multiplier
+    }
+
+    /// Returns the partial products, starting from the multiplier's least significant digit.
+    pub fn partials(&self) -> &[String] {
+        &self.partials
+    }
+
+    /// Returns the product word.
+    pub fn product(&self) -> &str {
+        &self.product
+    }
+
+    /// Checks whether `mapping` satisfies the puzzle in its base; see [`is_valid_product`].
+    pub fn is_solved_by(&self, mapping: &[(char, u32)]) -> bool {
+        is_valid_product_in_base(
+            &self.multiplicand,
+            &self.multiplier,
+            &self.partials,
+            &self.product,
+            mapping,
+            self.settings.base,
+        )
+    }
+}
+
+impl Alphametic for Multiplication {
+    /// Returns every word of the puzzle: the factors, the partial products and the product.
+    fn words(&self) -> Vec<&str> {
+        let mut words: Vec<&str> = vec![&self.multiplicand, &self.multiplier];
+        words.extend(self.partials.iter().map(String::as_str));
+        words.push(&self.product);
+        words
+    }
+
+    /// Returns an iterator over every solution.
+    ///
+    /// Letters are assigned column by column from the least significant digit,
+    /// and after each column the last `k` digits of every product are checked
+    /// modulo `base^k`, which prunes most assignments long before they are complete.
+    fn solutions(&self) -> Solutions {
+        let words: Vec<&str> = self.words();
+        let letters: Vec<char> = column_order(&words);
+
+        let puzzle: Multiplication = self.clone();
+        let verify = Box::new(move |mapping: &[(char, u32)]| puzzle.is_solved_by(mapping));
+        if letters.len() > self.settings.base as usize || self.check_layout().is_err() {
+            return Solutions::from_engine(letters, None, verify, self.settings.base);
+        }
+
+        let leading: HashSet<char> = if self.settings.allow_leading_zeros {
+            HashSet::new()
+        } else {
+            let factors: Vec<String> = words[..words.len() - 1]
+                .iter()
+                .map(|word| word.to_string())
+                .collect();
+            leading_letters(&factors, &self.product)
+        };
+        let mut solver = BacktrackSolver::new(
+            letters.iter().map(|c| leading.contains(c)).collect(),
+            self.settings.base,
+        );
+
+        let multiplicand: Vec<usize> = letter_indices(&self.multiplicand, &letters);
+        let multiplier: Vec<usize> = letter_indices(&self.multiplier, &letters);
+        add_product_checks(
+            &mut solver,
+            &multiplicand,
+            &multiplier,
+            &[],
+            &letter_indices(&self.product, &letters),
+            self.settings.base,
+        );
+        for (partial, &digit) in self.partials.iter().zip(multiplier.iter().rev()) {
+            add_product_checks(
+                &mut solver,
+                &multiplicand,
+                &[digit],
+                &[],
+                &letter_indices(partial, &letters),
+                self.settings.base,
+            );
+        }
+
+        Solutions::from_engine(
+            letters,
+            Some(Engine::Backtrack(solver)),
+            verify,
+            self.settings.base,
+        )
+        .ordered(&self.settings.letter_order.arrange(&words))
+    }
+
+    fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// Checks that there is one partial product per digit of the multiplier, if
+    /// any are given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Multiplication, SolveError};
+    ///
+    /// let puzzle: Multiplication = "AB * CD = EFGE; EAE".parse().unwrap();
+    /// assert_eq!(
+    ///     puzzle.validate(),
+    ///     Err(SolveError::PartialProducts { rows: 1, digits: 2 })
+    /// );
+    /// ```
+    fn check_layout(&self) -> Result<(), SolveError> {
+        let digits: usize = self.multiplier.chars().count();
+        if !self.partials.is_empty() && self.partials.len() != digits {
+            return Err(SolveError::PartialProducts {
+                rows: self.partials.len(),
+                digits,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Checks if the current mapping satisfies a multiplication puzzle.
+///
+/// # Parameters
+///
+/// - `multiplicand`: The word that is multiplied.
+/// - `multiplier`: The word it is multiplied by.
+/// - `partials`: The partial products, one per multiplier digit starting from the
+///   least significant, or an empty slice if the puzzle has none.
+/// - `product`: The result word.
+/// - `mapping`: A slice of tuples, each containing a character and its corresponding digit.
+///
+/// # Returns
+///
+/// `true` if the solution is valid, otherwise `false`.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::is_valid_product;
+///
+/// let mapping = [('A', 1), ('B', 4), ('C', 5), ('D', 7), ('E', 0)];
+/// assert!(is_valid_product("AB", "C", &[], "DE", &mapping));
+/// assert!(is_valid_product("AB", "C", &["DE".to_string()], "DE", &mapping));
+/// ```
+pub fn is_valid_product(
+    multiplicand: &str,
+    multiplier: &str,
+    partials: &[String],
+    product: &str,
+    mapping: &[(char, u32)],
+) -> bool {
+    is_valid_product_in_base(multiplicand, multiplier, partials, product, mapping, 10)
+}
+
+/// Like [`is_valid_product`], for a puzzle in `base`.
+fn is_valid_product_in_base(
+    multiplicand: &str,
+    multiplier: &str,
+    partials: &[String],
+    product: &str,
+    mapping: &[(char, u32)],
+    base: u32,
+) -> bool {
+    let value = |word: &str| word_to_number_in_base(word, mapping, base).ok();
+    let (Some(multiplicand_value), Some(multiplier_value), Some(product_value)) =
+        (value(multiplicand), value(multiplier), value(product))
+    else {
+        return false;
+    };
+    if multiplicand_value.checked_mul(multiplier_value) != Some(product_value) {
+        return false;
+    }
+    if partials.is_empty() {
+        return true;
+    }
+    partials.len() == multiplier.chars().count()
+        && partials
+            .iter()
+            .zip(multiplier.chars().rev())
+            .all(|(partial, letter)| {
+                let digit: Option<u128> = value(&letter.to_string());
+                let partial: Option<u128> = value(partial);
+                partial.is_some()
+                    && digit.and_then(|digit| multiplicand_value.checked_mul(digit)) == partial
+            })
+}
+
+impl fmt::Display for Multiplication {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} * {} = {}",
+            self.multiplicand, self.multiplier, self.product
+        )?;
+        if !self.partials.is_empty() {
+            write!(f, "; {}", self.partials.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Multiplication {
+    type Err = ParsePuzzleError;
+
+    /// Parses an equation such as `"AB * CD = EFGH"`.
+    ///
+    /// Partial products may follow a `;`, separated by commas and starting from
+    /// the multiplier's least significant digit, as in `"AB * CD = EFGH; IJK, LMN"`.
+    /// Whitespace is ignored and letters are converted to upper case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Multiplication, ParsePuzzleError};
+    ///
+    /// let puzzle: Multiplication = "AB * CD = EFGH; IJK, LMN".parse().unwrap();
+    /// assert_eq!(puzzle.partials(), &["IJK", "LMN"]);
+    /// assert_eq!("AB + CD = EFGH".parse::<Multiplication>(), Err(ParsePuzzleError::MissingOperator('*')));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (equation, partials) = match s.split_once(';') {
+            Some((equation, partials)) => (equation, Some(partials)),
+            None => (s, None),
+        };
+        let (left, right) = split_equation(equation)?;
+        let (multiplicand, multiplier) = left
+            .split_once('*')
+            .ok_or(ParsePuzzleError::MissingOperator('*'))?;
+        let partials: Vec<String> = match partials {
+            Some(partials) => partials
+                .split(',')
+                .map(parse_word)
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+        Ok(Multiplication::new(
+            parse_word(multiplicand)?,
+            parse_word(multiplier)?,
+            parse_word(right)?,
+        )
+        .with_partials(partials))
+    }
+}
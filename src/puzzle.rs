@@ -0,0 +1,336 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::alphametic::{Alphametic, Settings};
+use crate::expression::{terms_expr, Equation, Expr};
+use crate::render::render_sum;
+use crate::solution::Solution;
+use crate::solver::{is_valid_terms_solution_in_base, Solutions};
+
+/// Whether a term is added to or subtracted from the left-hand side of an equation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sign {
+    Plus,
+    Minus,
+}
+
+impl Sign {
+    /// Returns `1` for [`Sign::Plus`] and `-1` for [`Sign::Minus`].
+    pub fn coefficient(self) -> i64 {
+        match self {
+            Sign::Plus => 1,
+            Sign::Minus => -1,
+        }
+    }
+}
+
+/// A word on the left-hand side of an equation, together with its sign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub sign: Sign,
+    pub word: String,
+}
+
+impl Term {
+    /// Creates a term that is added.
+    pub fn plus(word: impl Into<String>) -> Self {
+        Term {
+            sign: Sign::Plus,
+            word: word.into(),
+        }
+    }
+
+    /// Creates a term that is subtracted.
+    pub fn minus(word: impl Into<String>) -> Self {
+        Term {
+            sign: Sign::Minus,
+            word: word.into(),
+        }
+    }
+}
+
+/// A crypto-arithmetic puzzle: a list of words, each added or subtracted, that
+/// must equal a result word.
+///
+/// A puzzle can be parsed from an equation such as `"SEND + MORE = MONEY"` or
+/// `"COUNT - COIN = SNUB"` and is printed back in the same form.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{Alphametic, Puzzle};
+///
+/// let puzzle: Puzzle = "send+more =  money".parse().unwrap();
+/// assert_eq!(puzzle.to_string(), "SEND + MORE = MONEY");
+/// let solution = puzzle.solve().unwrap();
+/// assert_eq!(solution.value("MONEY"), 10652);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    terms: Vec<Term>,
+    result: String,
+    settings: Settings,
+}
+
+impl Puzzle {
+    /// Creates a puzzle, following the leading-zero rule, in which all words are added.
+    ///
+    /// # Parameters
+    ///
+    /// - `words`: A vector of strings representing the words.
+    /// - `result`: A string representing the result word.
+    pub fn new(words: Vec<String>, result: String) -> Self {
+        Puzzle::with_terms(words.into_iter().map(Term::plus).collect(), result)
+    }
+
+    /// Creates a puzzle, following the leading-zero rule, from signed terms.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Puzzle, Term};
+    ///
+    /// let puzzle = Puzzle::with_terms(vec![Term::plus("COUNT"), Term::minus("COIN")], "SNUB".to_string());
+    /// assert_eq!(puzzle.to_string(), "COUNT - COIN = SNUB");
+    /// assert!(puzzle.solve().is_some());
+    /// ```
+    pub fn with_terms(terms: Vec<Term>, result: String) -> Self {
+        Puzzle {
+            terms,
+            result,
+            settings: Settings::default(),
+        }
+    }
+
+    /// Returns the signed words on the left-hand side of the equation.
+    pub fn terms(&self) -> &[Term] {
+        &self.terms
+    }
+
+    /// Returns the result word.
+    pub fn result(&self) -> &str {
+        &self.result
+    }
+
+    /// Converts the puzzle to a general [`Equation`] with the same solutions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::Puzzle;
+    ///
+    /// let puzzle: Puzzle = "COUNT - COIN = SNUB".parse().unwrap();
+    /// assert_eq!(puzzle.to_equation().to_string(), "COUNT - COIN = SNUB");
+    /// ```
+    pub fn to_equation(&self) -> Equation {
+        Equation::new(vec![
+            terms_expr(&self.terms),
+            Expr::word(self.result.as_str()),
+        ])
+        .allow_leading_zeros(self.settings.allow_leading_zeros)
+        .with_base(self.settings.base)
+        .with_letter_order(self.settings.letter_order)
+    }
+
+    /// Checks whether `mapping` satisfies the equation in the puzzle's base; see
+    /// [`is_valid_terms_solution`](crate::is_valid_terms_solution).
+    pub fn is_solved_by(&self, mapping: &[(char, u32)]) -> bool {
+        is_valid_terms_solution_in_base(&self.terms, &self.result, mapping, self.settings.base)
+    }
+
+    /// Renders the puzzle column by column next to its digits under `solution`,
+    /// with a row of carries on top.
+    ///
+    /// With `color`, each letter and its digits share an ANSI colour.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Puzzle};
+    ///
+    /// let puzzle: Puzzle = "SEND + MORE = MONEY".parse().unwrap();
+    /// let solution = puzzle.solve().unwrap();
+    /// assert_eq!(
+    ///     puzzle.render(&solution, false),
+    ///     [
+    ///         "             1 11",
+    ///         "   SEND       9567",
+    ///         "+  MORE    +  1085",
+    ///         "-------    -------",
+    ///         "  MONEY      10652",
+    ///     ]
+    ///     .join("\n")
+    /// );
+    /// ```
+    pub fn render(&self, solution: &Solution, color: bool) -> String {
+        render_sum(&self.terms, &self.result, solution, color)
+    }
+}
+
+impl Alphametic for Puzzle {
+    /// Returns every word of the puzzle, from left to right, ending with the result.
+    fn words(&self) -> Vec<&str> {
+        self.terms
+            .iter()
+            .map(|term| term.word.as_str())
+            .chain(std::iter::once(self.result.as_str()))
+            .collect()
+    }
+
+    /// Returns an iterator over every solution.
+    fn solutions(&self) -> Solutions {
+        Solutions::new(
+            self.terms.clone(),
+            self.result.clone(),
+            self.settings.allow_leading_zeros,
+            self.settings.base,
+            self.settings.letter_order,
+        )
+    }
+
+    fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, term) in self.terms.iter().enumerate() {
+            match (i, term.sign) {
+                (0, Sign::Plus) => write!(f, "{}", term.word)?,
+                (0, Sign::Minus) => write!(f, "-{}", term.word)?,
+                (_, Sign::Plus) => write!(f, " + {}", term.word)?,
+                (_, Sign::Minus) => write!(f, " - {}", term.word)?,
+            }
+        }
+        write!(f, " = {}", self.result)
+    }
+}
+
+/// An error returned when parsing a [`Puzzle`] from an equation fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePuzzleError {
+    /// The equation has no `=` sign.
+    MissingEquals,
+    /// The equation has more than one `=` sign.
+    MultipleEquals,
+    /// A word between operators is empty, as in `"SEND + - MONEY"` or `"SEND + = MONEY"`.
+    EmptyWord,
+    /// A word contains a character that is not a letter.
+    InvalidCharacter(char),
+    /// The equation lacks the operator this kind of puzzle requires.
+    MissingOperator(char),
+    /// The rows of a long-division layout do not come in product/difference pairs.
+    UnpairedRow,
+    /// A parenthesis is not matched.
+    UnbalancedParenthesis,
+    /// A word or number appears where an operator was expected.
+    UnexpectedToken(String),
+}
+
+impl fmt::Display for ParsePuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParsePuzzleError::MissingEquals => write!(f, "missing '=' in equation"),
+            ParsePuzzleError::MultipleEquals => write!(f, "more than one '=' in equation"),
+            ParsePuzzleError::EmptyWord => write!(f, "empty word in equation"),
+            ParsePuzzleError::InvalidCharacter(c) => write!(f, "invalid character {:?} in word", c),
+            ParsePuzzleError::MissingOperator(c) => write!(f, "missing '{}' in equation", c),
+            ParsePuzzleError::UnpairedRow => write!(f, "division rows must come in pairs"),
+            ParsePuzzleError::UnbalancedParenthesis => write!(f, "unbalanced parenthesis"),
+            ParsePuzzleError::UnexpectedToken(token) => {
+                write!(f, "unexpected {:?} in equation", token)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParsePuzzleError {}
+
+/// Panics unless `base` is a supported numeric base, from 2 to 36.
+pub(crate) fn assert_base(base: u32) {
+    assert!(
+        (2..=36).contains(&base),
+        "base must be between 2 and 36, got {}",
+        base
+    );
+}
+
+/// Normalizes a single word of an equation to upper case.
+pub(crate) fn parse_word(word: &str) -> Result<String, ParsePuzzleError> {
+    let word: &str = word.trim();
+    if word.is_empty() {
+        return Err(ParsePuzzleError::EmptyWord);
+    }
+    if let Some(c) = word.chars().find(|c| !c.is_alphabetic()) {
+        return Err(ParsePuzzleError::InvalidCharacter(c));
+    }
+    Ok(word.to_uppercase())
+}
+
+/// Splits an equation into the text on either side of its single `=` sign.
+pub(crate) fn split_equation(s: &str) -> Result<(&str, &str), ParsePuzzleError> {
+    let mut sides = s.split('=');
+    let left: &str = sides.next().unwrap_or_default();
+    let right: &str = sides.next().ok_or(ParsePuzzleError::MissingEquals)?;
+    if sides.next().is_some() {
+        return Err(ParsePuzzleError::MultipleEquals);
+    }
+    Ok((left, right))
+}
+
+impl FromStr for Puzzle {
+    type Err = ParsePuzzleError;
+
+    /// Parses an equation such as `"SEND + MORE = MONEY"` or `"COUNT - COIN = SNUB"`.
+    ///
+    /// The first word may carry a sign of its own, as in `"-A + B = C"`.
+    /// Whitespace around words and operators is ignored and letters are
+    /// converted to upper case. The parsed puzzle follows the leading-zero rule.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{ParsePuzzleError, Puzzle, Term};
+    ///
+    /// let puzzle: Puzzle = " to + go - a = out ".parse().unwrap();
+    /// assert_eq!(puzzle.terms(), &[Term::plus("TO"), Term::plus("GO"), Term::minus("A")]);
+    /// assert_eq!(puzzle.result(), "OUT");
+    /// assert_eq!("TO + GO".parse::<Puzzle>(), Err(ParsePuzzleError::MissingEquals));
+    /// assert_eq!("TO + G0 = OUT".parse::<Puzzle>(), Err(ParsePuzzleError::InvalidCharacter('0')));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (left, right) = split_equation(s)?;
+        let mut terms: Vec<Term> = Vec::new();
+        let mut left: &str = left.trim_start();
+        let mut sign: Sign = match left.chars().next() {
+            Some('-') => Sign::Minus,
+            _ => Sign::Plus,
+        };
+        if let Some(rest) = left.strip_prefix(['+', '-']) {
+            left = rest;
+        }
+        while let Some(end) = left.find(['+', '-']) {
+            terms.push(Term {
+                sign,
+                word: parse_word(&left[..end])?,
+            });
+            sign = if left[end..].starts_with('-') {
+                Sign::Minus
+            } else {
+                Sign::Plus
+            };
+            left = &left[end + 1..];
+        }
+        terms.push(Term {
+            sign,
+            word: parse_word(left)?,
+        });
+        let result: String = parse_word(right)?;
+        Ok(Puzzle::with_terms(terms, result))
+    }
+}
@@ -0,0 +1,95 @@
+use crate::puzzle::{Sign, Term};
+use crate::solution::Solution;
+
+/// Foreground colours given to letters, in alphabetical order of the letters.
+const PALETTE: [u8; 12] = [31, 32, 33, 34, 35, 36, 91, 92, 93, 94, 95, 96];
+
+/// Lays out a sum of signed terms in columns, with the letters on the left and
+/// the digits of `solution` on the right, under a row of carries.
+///
+/// Each column of the carry row shows the carry into that digit: blank for
+/// none, a digit of the puzzle's base otherwise, `-` for a borrow and `+` for a
+/// carry too large for one digit. With `color`, every letter and its digits are
+/// wrapped in the same ANSI colour.
+pub(crate) fn render_sum(terms: &[Term], result: &str, solution: &Solution, color: bool) -> String {
+    let width: usize = terms
+        .iter()
+        .map(|term| term.word.as_str())
+        .chain(std::iter::once(result))
+        .map(|word| word.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut letters: Vec<char> = solution.mapping().iter().map(|&(ch, _)| ch).collect();
+    letters.sort();
+    let paint = |ch: char, text: char| -> String {
+        match letters.iter().position(|&letter| letter == ch) {
+            Some(i) if color => format!("\x1b[{}m{}\x1b[0m", PALETTE[i % PALETTE.len()], text),
+            _ => text.to_string(),
+        }
+    };
+    let digit_char = |ch: char| -> char {
+        let digit: u32 = solution.digit(ch).unwrap_or(0);
+        char::from_digit(digit, 36)
+            .unwrap_or('?')
+            .to_ascii_uppercase()
+    };
+    // Each row is the sign, then the word right-aligned to `width`, as letters and as digits.
+    let row = |sign: &str, word: &str| -> String {
+        let pad: String = " ".repeat(width - word.chars().count());
+        let letters: String = word.chars().map(|ch| paint(ch, ch)).collect();
+        let digits: String = word.chars().map(|ch| paint(ch, digit_char(ch))).collect();
+        format!("{} {}{}    {} {}{}", sign, pad, letters, sign, pad, digits)
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    let carries: String = carries(terms, solution, width)
+        .into_iter()
+        .rev()
+        .map(|carry| match carry {
+            0 => ' ',
+            1..=35 => char::from_digit(carry as u32, 36)
+                .unwrap()
+                .to_ascii_uppercase(),
+            c if c < 0 => '-',
+            _ => '+',
+        })
+        .collect();
+    lines.push(format!("{}{}", " ".repeat(width + 8), carries));
+    for (i, term) in terms.iter().enumerate() {
+        let sign: &str = match (i, term.sign) {
+            (0, Sign::Plus) => " ",
+            (_, Sign::Plus) => "+",
+            (_, Sign::Minus) => "-",
+        };
+        lines.push(row(sign, &term.word));
+    }
+    let rule: String = "-".repeat(width + 2);
+    lines.push(format!("{}    {}", rule, rule));
+    lines.push(row(" ", result));
+    lines
+        .iter()
+        .map(|line| line.trim_end().to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Returns the carry into each column of the sum, from the least significant.
+fn carries(terms: &[Term], solution: &Solution, width: usize) -> Vec<i64> {
+    let base: i64 = i64::from(solution.base());
+    let mut carries: Vec<i64> = vec![0; width];
+    let mut carry: i64 = 0;
+    for (col, slot) in carries.iter_mut().enumerate() {
+        *slot = carry;
+        let sum: i64 = carry
+            + terms
+                .iter()
+                .filter_map(|term| {
+                    let ch: char = term.word.chars().rev().nth(col)?;
+                    Some(term.sign.coefficient() * i64::from(solution.digit(ch)?))
+                })
+                .sum::<i64>();
+        carry = sum.div_euclid(base);
+    }
+    carries
+}
@@ -0,0 +1,325 @@
+use std::sync::Arc;
+
+use crate::limit::{Interrupt, Stop};
+use crate::stats::{Rule, Stats};
+
+/// A constraint over the digits assigned so far, indexed by letter.
+///
+/// A check is only run once every letter it reads has been assigned; the
+/// remaining entries of the slice are meaningless at that point. Checks are
+/// shared between the parts of a split search, which may run on other threads.
+pub(crate) type Check = Arc<dyn Fn(&[u32]) -> bool + Send + Sync>;
+
+/// Backtracking search that assigns letters in a fixed order and prunes with checks.
+///
+/// Letter `i` is assigned at depth `i`, and every check registered for depth `i`
+/// is run right after, so a failing constraint cuts off the whole subtree. Like
+/// the column solver, the search keeps an explicit stack so it can be paused
+/// after each solution and resumed.
+#[derive(Clone)]
+pub(crate) struct BacktrackSolver {
+    nonzero: Vec<bool>,
+    /// The checks of each depth, with the rule each one enforces.
+    checks: Vec<Vec<(Rule, Check)>>,
+    assignment: Vec<u32>,
+    base: u32,
+    used: Vec<bool>,
+    /// Next candidate digit of each letter; the current digit is the one before it.
+    cursors: Vec<u32>,
+    /// One past the last digit tried for the first letter; below `base` in a split search.
+    end: u32,
+    pub(crate) interrupt: Interrupt,
+    pub(crate) stats: Stats,
+    /// Why the search ended early, if it did.
+    pub(crate) stopped: Option<Stop>,
+    depth: usize,
+    done: bool,
+}
+
+impl BacktrackSolver {
+    /// Creates a solver for `nonzero.len()` letters in `base`; `nonzero[i]` forbids zero for letter `i`.
+    pub(crate) fn new(nonzero: Vec<bool>, base: u32) -> Self {
+        let len: usize = nonzero.len();
+        BacktrackSolver {
+            nonzero,
+            checks: (0..len).map(|_| Vec::new()).collect(),
+            assignment: vec![0; len],
+            base,
+            used: vec![false; base as usize],
+            cursors: vec![0; len],
+            end: base,
+            interrupt: Interrupt::default(),
+            stats: Stats::default(),
+            stopped: None,
+            depth: 0,
+            done: false,
+        }
+    }
+
+    /// Registers `check`, which enforces `rule`, to run as soon as letters `0..=ready` are assigned.
+    pub(crate) fn add_check(&mut self, ready: usize, rule: Rule, check: Check) {
+        self.checks[ready].push((rule, check));
+    }
+
+    /// Moves the letter at the current depth on to its next digit that passes every check.
+    fn try_step(&mut self) -> bool {
+        let depth: usize = self.depth;
+        if self.cursors[depth] > 0 {
+            self.used[self.assignment[depth] as usize] = false;
+        }
+        let end: u32 = if depth == 0 { self.end } else { self.base };
+        while self.cursors[depth] < end {
+            let digit: u32 = self.cursors[depth];
+            self.cursors[depth] += 1;
+            self.stats.nodes += 1;
+            if self.used[digit as usize] {
+                self.stats.prunes.count(Rule::DigitTaken);
+                continue;
+            }
+            if digit == 0 && self.nonzero[depth] {
+                self.stats.prunes.count(Rule::LeadingZero);
+                continue;
+            }
+            self.assignment[depth] = digit;
+            let failed: Option<Rule> = self.checks[depth]
+                .iter()
+                .find(|(_, check)| !check(&self.assignment))
+                .map(|&(rule, _)| rule);
+            match failed {
+                Some(rule) => self.stats.prunes.count(rule),
+                None => {
+                    self.used[digit as usize] = true;
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Resumes the search and stops at the next solution.
+    ///
+    /// Returns `false` once the search space is exhausted.
+    pub(crate) fn next_solution(&mut self) -> bool {
+        if self.done {
+            return false;
+        }
+        if self.assignment.is_empty() {
+            self.done = true;
+            return true;
+        }
+        loop {
+            if self.interrupt.poll(&self.stats) {
+                self.stopped = self.interrupt.reason();
+                self.done = true;
+                return false;
+            }
+            if self.try_step() {
+                self.stats.max_depth = self.stats.max_depth.max(self.depth + 1);
+                if self.depth + 1 == self.assignment.len() {
+                    return true;
+                }
+                self.depth += 1;
+                self.cursors[self.depth] = 0;
+            } else if self.depth == 0 {
+                self.done = true;
+                return false;
+            } else {
+                self.depth -= 1;
+                self.stats.backtracks += 1;
+            }
+        }
+    }
+
+    /// Pairs each letter with its assigned digit, in the order of `letters`.
+    pub(crate) fn mapping(&self, letters: &[char]) -> Vec<(char, u32)> {
+        letters
+            .iter()
+            .cloned()
+            .zip(self.assignment.iter().cloned())
+            .collect()
+    }
+
+    /// Splits a search that has not started into one part per digit of the first
+    /// letter, in order; any other search is returned whole.
+    pub(crate) fn split(self) -> Vec<BacktrackSolver> {
+        if self.done || self.assignment.is_empty() || self.cursors[0] > 0 || self.end < self.base {
+            return vec![self];
+        }
+        (0..self.base)
+            .map(|digit| {
+                let mut part: BacktrackSolver = self.clone();
+                part.cursors[0] = digit;
+                part.end = digit + 1;
+                part
+            })
+            .collect()
+    }
+}
+
+/// Orders the letters of `words` column by column, from the least significant digit.
+///
+/// Assigning letters in this order lets checks on the last `k` digits of each
+/// word run as early as possible.
+pub(crate) fn column_order(words: &[&str]) -> Vec<char> {
+    let width: usize = words
+        .iter()
+        .map(|word| word.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut letters: Vec<char> = Vec::new();
+    for i in 0..width {
+        for word in words {
+            if let Some(c) = word.chars().rev().nth(i) {
+                if !letters.contains(&c) {
+                    letters.push(c);
+                }
+            }
+        }
+    }
+    letters
+}
+
+/// Converts a word to the indices of its letters in `letters`, most significant first.
+pub(crate) fn letter_indices(word: &str, letters: &[char]) -> Vec<usize> {
+    word.chars()
+        .map(|c| letters.iter().position(|&l| l == c).unwrap())
+        .collect()
+}
+
+/// Returns the value of the last `k` digits of `word` under `assignment`.
+pub(crate) fn suffix_value(word: &[usize], k: usize, assignment: &[u32], base: u32) -> u128 {
+    let start: usize = word.len().saturating_sub(k);
+    word[start..].iter().fold(0, |number, &letter| {
+        number * u128::from(base) + u128::from(assignment[letter])
+    })
+}
+
+/// Returns the value of `word` under `assignment`, or `None` if it overflows a `u128`.
+pub(crate) fn word_value(word: &[usize], assignment: &[u32], base: u32) -> Option<u128> {
+    word.iter().try_fold(0u128, |number, &letter| {
+        number
+            .checked_mul(u128::from(base))?
+            .checked_add(u128::from(assignment[letter]))
+    })
+}
+
+/// Returns the widest column `k` for which the product of two residues modulo
+/// `base^k` still fits in an `i128`.
+pub(crate) fn max_modular_column(base: u32) -> usize {
+    let mut k: usize = 0;
+    let mut modulus: u128 = 1;
+    while let Some(next) = modulus.checked_mul(u128::from(base)) {
+        match next.checked_mul(next) {
+            Some(square) if square <= i128::MAX as u128 => {
+                modulus = next;
+                k += 1;
+            }
+            _ => break,
+        }
+    }
+    k
+}
+
+/// Returns the highest letter index read by the last `k` digits of any of `words`.
+pub(crate) fn suffix_ready(words: &[&[usize]], k: usize) -> usize {
+    words
+        .iter()
+        .flat_map(|word| &word[word.len().saturating_sub(k)..])
+        .cloned()
+        .max()
+        .unwrap_or(0)
+}
+
+/// Adds checks that `left * right + addend == total`, one per column of the total.
+///
+/// The last `k` digits of a product only depend on the last `k` digits of its
+/// factors, so each check runs as soon as those letters are assigned. The final
+/// column is wide enough for the comparison to be exact; if that is too wide for
+/// modular arithmetic, a last check compares the full values instead. Pass an
+/// empty `addend` for a plain product.
+pub(crate) fn add_product_checks(
+    solver: &mut BacktrackSolver,
+    left: &[usize],
+    right: &[usize],
+    addend: &[usize],
+    total: &[usize],
+    base: u32,
+) {
+    let width: usize = total.len().max(left.len() + right.len()).max(addend.len()) + 1;
+    let columns: usize = width.min(max_modular_column(base));
+    let words: [&[usize]; 4] = [left, right, addend, total];
+    let (left, right, addend, total) = (
+        left.to_vec(),
+        right.to_vec(),
+        addend.to_vec(),
+        total.to_vec(),
+    );
+    for k in 1..=columns {
+        let (left, right, addend, total) =
+            (left.clone(), right.clone(), addend.clone(), total.clone());
+        let modulus: u128 = u128::from(base).pow(k as u32);
+        solver.add_check(
+            suffix_ready(&words, k),
+            Rule::Suffix,
+            Arc::new(move |assignment| {
+                let value: u128 = suffix_value(&left, k, assignment, base)
+                    * suffix_value(&right, k, assignment, base)
+                    + suffix_value(&addend, k, assignment, base);
+                value % modulus == suffix_value(&total, k, assignment, base)
+            }),
+        );
+    }
+    if columns < width {
+        solver.add_check(
+            suffix_ready(&words, width),
+            Rule::Value,
+            Arc::new(move |assignment| {
+                let value: Option<u128> = word_value(&left, assignment, base).and_then(|left| {
+                    left.checked_mul(word_value(&right, assignment, base)?)?
+                        .checked_add(word_value(&addend, assignment, base)?)
+                });
+                value.is_some() && value == word_value(&total, assignment, base)
+            }),
+        );
+    }
+}
+
+/// Adds checks that `sum(coefficients[i] * digit[i]) == 0`, one per letter.
+///
+/// After each letter is assigned, the letters still to come can add at least
+/// their positive coefficients times the smallest free digit plus their
+/// negative coefficients times the largest, and at most the reverse; the
+/// branch is cut as soon as zero falls outside that range. Letters should be
+/// ordered by decreasing magnitude of their coefficient, so the bound tightens
+/// quickly.
+pub(crate) fn add_linear_checks(solver: &mut BacktrackSolver, coefficients: &[i128], base: u32) {
+    let len: usize = coefficients.len();
+    for ready in 0..len {
+        let assigned: Vec<i128> = coefficients[..=ready].to_vec();
+        let rest: &[i128] = &coefficients[ready + 1..];
+        let positive: i128 = rest.iter().filter(|&&c| c > 0).sum();
+        let negative: i128 = rest.iter().filter(|&&c| c < 0).sum();
+        solver.add_check(
+            ready,
+            Rule::Bounds,
+            Arc::new(move |assignment| {
+                let partial: i128 = assigned
+                    .iter()
+                    .zip(assignment)
+                    .map(|(&c, &digit)| c * i128::from(digit))
+                    .sum();
+                if ready + 1 == len {
+                    return partial == 0;
+                }
+                let mut used: Vec<bool> = vec![false; base as usize];
+                for &digit in &assignment[..=ready] {
+                    used[digit as usize] = true;
+                }
+                let low: i128 = used.iter().position(|&used| !used).unwrap_or(0) as i128;
+                let high: i128 = used.iter().rposition(|&used| !used).unwrap_or(0) as i128;
+                partial + positive * low + negative * high <= 0
+                    && partial + positive * high + negative * low >= 0
+            }),
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use std::cmp::Reverse;
+
+use crate::solver::{word_to_number_in_base, SolveError};
+
+/// The order in which the letters of a puzzle are listed in each [`Solution`].
+///
+/// Every puzzle type uses [`LetterOrder::FirstAppearance`] unless another order is
+/// set with its `with_letter_order` builder. The order only affects how mappings
+/// are listed; the search itself, and so which solution is found first, is the
+/// same for a given puzzle on every run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LetterOrder {
+    /// In the order the letters first appear, reading the words left to right.
+    #[default]
+    FirstAppearance,
+    /// In alphabetical order.
+    Alphabetical,
+    /// By the most significant column a letter occupies in any word, leftmost
+    /// first; letters that reach the same column keep their order of first appearance.
+    MostSignificantFirst,
+}
+
+impl LetterOrder {
+    /// Returns the distinct letters of `words` in this order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::LetterOrder;
+    ///
+    /// let words = ["SEND", "MORE", "MONEY"];
+    /// let order = |order: LetterOrder| order.arrange(&words).into_iter().collect::<String>();
+    /// assert_eq!(order(LetterOrder::FirstAppearance), "SENDMORY");
+    /// assert_eq!(order(LetterOrder::Alphabetical), "DEMNORSY");
+    /// assert_eq!(order(LetterOrder::MostSignificantFirst), "MSOENRDY");
+    /// ```
+    pub fn arrange(self, words: &[&str]) -> Vec<char> {
+        let mut letters: Vec<char> = Vec::new();
+        for word in words {
+            for c in word.chars() {
+                if !letters.contains(&c) {
+                    letters.push(c);
+                }
+            }
+        }
+        match self {
+            LetterOrder::FirstAppearance => {}
+            LetterOrder::Alphabetical => letters.sort(),
+            LetterOrder::MostSignificantFirst => letters.sort_by_key(|&letter| {
+                let column = words
+                    .iter()
+                    .flat_map(|word| word.chars().rev().enumerate())
+                    .filter(|&(_, c)| c == letter)
+                    .map(|(column, _)| column)
+                    .max();
+                Reverse(column)
+            }),
+        }
+        letters
+    }
+}
+
+/// A letter-to-digit assignment that solves a puzzle.
+///
+/// The mapping keeps the same `(char, digit)` representation used throughout the
+/// crate, so it can be passed to [`word_to_number`](crate::word_to_number) or
+/// [`is_valid_solution`](crate::is_valid_solution) via [`Solution::mapping`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    mapping: Vec<(char, u32)>,
+    base: u32,
+}
+
+impl Solution {
+    /// Wraps a letter-to-digit mapping for a base 10 puzzle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::Solution;
+    ///
+    /// let solution = Solution::new(vec![('A', 1), ('B', 2)]);
+    /// assert_eq!(solution.mapping(), &[('A', 1), ('B', 2)]);
+    /// ```
+    pub fn new(mapping: Vec<(char, u32)>) -> Self {
+        Solution::in_base(mapping, 10)
+    }
+
+    /// Wraps a letter-to-digit mapping for a puzzle in `base`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::Solution;
+    ///
+    /// let solution = Solution::in_base(vec![('A', 10), ('B', 1)], 16);
+    /// assert_eq!(solution.value("BA"), 0x1A);
+    /// ```
+    pub fn in_base(mapping: Vec<(char, u32)>, base: u32) -> Self {
+        Solution { mapping, base }
+    }
+
+    /// Returns the numeric base of the puzzle this solution belongs to.
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// Returns the mapping as a slice of `(char, digit)` tuples.
+    pub fn mapping(&self) -> &[(char, u32)] {
+        &self.mapping
+    }
+
+    /// Returns the digit assigned to `letter`, if it is part of the solution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::Solution;
+    ///
+    /// let solution = Solution::new(vec![('A', 1), ('B', 2)]);
+    /// assert_eq!(solution.digit('B'), Some(2));
+    /// assert_eq!(solution.digit('C'), None);
+    /// ```
+    pub fn digit(&self, letter: char) -> Option<u32> {
+        self.mapping
+            .iter()
+            .find(|&&(ch, _)| ch == letter)
+            .map(|&(_, digit)| digit)
+    }
+
+    /// Returns the numerical value of `word` under this solution, in its base.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `word` contains a letter that is not part of the solution, or if
+    /// its value does not fit in a `u128`; see [`Solution::try_value`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::Solution;
+    ///
+    /// let solution = Solution::new(vec![('A', 1), ('B', 2)]);
+    /// assert_eq!(solution.value("BAB"), 212);
+    /// assert_eq!(solution.value("ABABABABABABABABABAB"), 12_121_212_121_212_121_212);
+    /// ```
+    pub fn value(&self, word: &str) -> u128 {
+        self.try_value(word)
+            .unwrap_or_else(|error| panic!("cannot evaluate {}: {}", word, error))
+    }
+
+    /// Returns the numerical value of `word` under this solution, or why it has none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Solution, SolveError};
+    ///
+    /// let solution = Solution::new(vec![('A', 1), ('B', 2)]);
+    /// assert_eq!(solution.try_value("AB"), Ok(12));
+    /// assert_eq!(solution.try_value("ABC"), Err(SolveError::MissingLetter('C')));
+    /// ```
+    pub fn try_value(&self, word: &str) -> Result<u128, SolveError> {
+        word_to_number_in_base(word, &self.mapping, self.base)
+    }
+
+    /// Returns the letters of the solution, in mapping order, as a single string.
+    pub fn letters(&self) -> String {
+        self.mapping.iter().map(|&(ch, _)| ch).collect()
+    }
+}
+
+impl From<Solution> for Vec<(char, u32)> {
+    fn from(solution: Solution) -> Self {
+        solution.mapping
+    }
+}
+
+impl IntoIterator for Solution {
+    type Item = (char, u32);
+    type IntoIter = std::vec::IntoIter<(char, u32)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.mapping.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Solution {
+    type Item = &'a (char, u32);
+    type IntoIter = std::slice::Iter<'a, (char, u32)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.mapping.iter()
+    }
+}
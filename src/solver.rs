@@ -0,0 +1,1276 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::alphametic::Alphametic;
+use crate::limit::{CancelToken, Interrupt, Stop};
+use crate::puzzle::{Puzzle, Term};
+use crate::search::{add_linear_checks, BacktrackSolver};
+use crate::solution::{LetterOrder, Solution};
+use crate::stats::{Progress, Rule, Stats};
+
+/// An error explaining why a puzzle could not be solved or evaluated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The puzzle has more distinct letters than its base has digits.
+    TooManyLetters { letters: usize, base: u32 },
+    /// A word of the puzzle is empty.
+    EmptyWord,
+    /// A word contains a character that is not a letter.
+    InvalidCharacter(char),
+    /// A word contains a letter that the mapping does not assign.
+    MissingLetter(char),
+    /// The value of a word does not fit in a `u128`.
+    Overflow,
+    /// A multiplication gives partial products, but not one per digit of its multiplier.
+    PartialProducts { rows: usize, digits: usize },
+    /// A long division gives more steps than its quotient has digits.
+    DivisionSteps { steps: usize, digits: usize },
+    /// The puzzle is well-formed but has no solution.
+    Unsatisfiable,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::TooManyLetters { letters, base } => write!(
+                f,
+                "{} distinct letters, but base {} only has {} digits",
+                letters, base, base
+            ),
+            SolveError::EmptyWord => write!(f, "empty word in puzzle"),
+            SolveError::InvalidCharacter(c) => write!(f, "invalid character {:?} in word", c),
+            SolveError::MissingLetter(c) => write!(f, "letter {:?} has no digit", c),
+            SolveError::Overflow => write!(f, "word value does not fit in a u128"),
+            SolveError::PartialProducts { rows, digits } => write!(
+                f,
+                "{} partial products, but the multiplier has {} digits",
+                rows, digits
+            ),
+            SolveError::DivisionSteps { steps, digits } => write!(
+                f,
+                "{} division steps, but the quotient only has {} digits",
+                steps, digits
+            ),
+            SolveError::Unsatisfiable => write!(f, "the puzzle has no solution"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Checks that every word is non-empty and made of letters, and that `words`
+/// have no more distinct letters than `base` has digits.
+pub(crate) fn validate_words(words: &[&str], base: u32) -> Result<(), SolveError> {
+    let mut letters: HashSet<char> = HashSet::new();
+    for word in words {
+        if word.is_empty() {
+            return Err(SolveError::EmptyWord);
+        }
+        if let Some(c) = word.chars().find(|c| !c.is_alphabetic()) {
+            return Err(SolveError::InvalidCharacter(c));
+        }
+        letters.extend(word.chars());
+    }
+    if letters.len() > base as usize {
+        return Err(SolveError::TooManyLetters {
+            letters: letters.len(),
+            base,
+        });
+    }
+    Ok(())
+}
+
+/// Converts a word to its numerical value based on the given character-to-digit mapping.
+///
+/// # Parameters
+///
+/// - `word`: A string slice representing the word to convert.
+/// - `mapping`: A slice of tuples, each containing a character and its corresponding digit.
+///
+/// # Returns
+///
+/// The numerical value of the word as a `u128`, which holds words of up to 38 digits.
+///
+/// # Errors
+///
+/// Returns [`SolveError::MissingLetter`] if `word` contains a letter missing from
+/// `mapping`, or [`SolveError::Overflow`] if its value does not fit in a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{word_to_number, SolveError};
+///
+/// let mapping = [('A', 1), ('B', 2), ('C', 3)];
+/// let result = word_to_number("ABC", &mapping);
+/// assert_eq!(result, Ok(123));
+/// assert_eq!(word_to_number("ABD", &mapping), Err(SolveError::MissingLetter('D')));
+/// ```
+pub fn word_to_number(word: &str, mapping: &[(char, u32)]) -> Result<u128, SolveError> {
+    word_to_number_in_base(word, mapping, 10)
+}
+
+/// Converts a word to its numerical value in the given base.
+///
+/// # Parameters
+///
+/// - `word`: A string slice representing the word to convert.
+/// - `mapping`: A slice of tuples, each containing a character and its corresponding digit.
+/// - `base`: The numeric base of the puzzle, from 2 to 36.
+///
+/// # Returns
+///
+/// The numerical value of the word as a `u128`.
+///
+/// # Errors
+///
+/// The same as [`word_to_number`].
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::word_to_number_in_base;
+///
+/// let mapping = [('A', 1), ('B', 15)];
+/// assert_eq!(word_to_number_in_base("AB", &mapping, 16), Ok(0x1F));
+/// ```
+pub fn word_to_number_in_base(
+    word: &str,
+    mapping: &[(char, u32)],
+    base: u32,
+) -> Result<u128, SolveError> {
+    let mut number: u128 = 0;
+    for c in word.chars() {
+        let digit: u32 = mapping
+            .iter()
+            .find(|&&(ch, _)| ch == c)
+            .ok_or(SolveError::MissingLetter(c))?
+            .1;
+        number = number
+            .checked_mul(u128::from(base))
+            .and_then(|number| number.checked_add(u128::from(digit)))
+            .ok_or(SolveError::Overflow)?;
+    }
+    Ok(number)
+}
+
+/// Checks if the current mapping satisfies the puzzle.
+///
+/// # Parameters
+///
+/// - `words`: A vector of strings representing the words.
+/// - `result`: A string representing the result word.
+/// - `mapping`: A slice of tuples, each containing a character and its corresponding digit.
+///
+/// # Returns
+///
+/// `true` if the solution is valid, otherwise `false`.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::is_valid_solution;
+///
+/// let words = vec!["SEND".to_string(), "MORE".to_string()];
+/// let result = "MONEY".to_string();
+/// let mapping = [('S', 9), ('E', 5), ('N', 6), ('D', 7), ('M', 1), ('O', 0), ('R', 8), ('Y', 2)];
+/// assert!(is_valid_solution(&words, &result, &mapping));
+/// ```
+pub fn is_valid_solution(words: &[String], result: &str, mapping: &[(char, u32)]) -> bool {
+    let terms: Vec<Term> = words.iter().map(Term::plus).collect();
+    is_valid_terms_solution(&terms, result, mapping)
+}
+
+/// Checks if the current mapping satisfies an equation of signed terms.
+///
+/// The equation is checked column by column with a signed carry, so words of any
+/// length and any number of terms are handled without overflow, and differences
+/// that go negative along the way are handled correctly.
+///
+/// # Parameters
+///
+/// - `terms`: A slice of signed words on the left-hand side of the equation.
+/// - `result`: A string slice representing the result word.
+/// - `mapping`: A slice of tuples, each containing a character and its corresponding digit.
+///
+/// # Returns
+///
+/// `true` if the solution is valid, otherwise `false`, including when a letter
+/// is missing from `mapping`.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{is_valid_terms_solution, Term};
+///
+/// let terms = vec![Term::plus("AB"), Term::minus("C")];
+/// assert!(is_valid_terms_solution(&terms, "D", &[('A', 1), ('B', 2), ('C', 5), ('D', 7)]));
+/// ```
+pub fn is_valid_terms_solution(terms: &[Term], result: &str, mapping: &[(char, u32)]) -> bool {
+    is_valid_terms_solution_in_base(terms, result, mapping, 10)
+}
+
+/// Like [`is_valid_terms_solution`], for a puzzle in `base`.
+pub(crate) fn is_valid_terms_solution_in_base(
+    terms: &[Term],
+    result: &str,
+    mapping: &[(char, u32)],
+    base: u32,
+) -> bool {
+    let digits = |word: &str| -> Option<Vec<i64>> {
+        word.chars()
+            .rev()
+            .map(|c| {
+                let &(_, digit) = mapping.iter().find(|&&(ch, _)| ch == c)?;
+                Some(i64::from(digit))
+            })
+            .collect()
+    };
+    let terms: Option<Vec<(i64, Vec<i64>)>> = terms
+        .iter()
+        .map(|term| Some((term.sign.coefficient(), digits(&term.word)?)))
+        .collect();
+    let (Some(terms), Some(result)) = (terms, digits(result)) else {
+        return false;
+    };
+    let width: usize = terms
+        .iter()
+        .map(|(_, digits)| digits.len())
+        .chain(std::iter::once(result.len()))
+        .max()
+        .unwrap_or(0);
+
+    let base: i64 = i64::from(base);
+    let mut carry: i64 = 0;
+    for col in 0..width {
+        let sum: i64 = carry
+            + terms
+                .iter()
+                .filter_map(|(sign, digits)| Some(sign * digits.get(col)?))
+                .sum::<i64>()
+            - result.get(col).copied().unwrap_or(0);
+        if sum.rem_euclid(base) != 0 {
+            return false;
+        }
+        carry = sum.div_euclid(base);
+    }
+    carry == 0
+}
+
+/// Collects the first letter of every multi-letter word in the puzzle.
+///
+/// By convention these letters may not be assigned zero, since numbers are
+/// never written with a leading zero. Single-letter words are exempt.
+///
+/// # Parameters
+///
+/// - `words`: A slice of strings representing the words.
+/// - `result`: A string slice representing the result word.
+///
+/// # Returns
+///
+/// The set of letters that start a word of two or more letters.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::leading_letters;
+///
+/// let words = vec!["SEND".to_string(), "MORE".to_string(), "I".to_string()];
+/// let leading = leading_letters(&words, "MONEY");
+/// assert!(leading.contains(&'S') && leading.contains(&'M'));
+/// assert!(!leading.contains(&'I'));
+/// ```
+pub fn leading_letters(words: &[String], result: &str) -> HashSet<char> {
+    words
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(result))
+        .filter(|word| word.chars().count() > 1)
+        .filter_map(|word| word.chars().next())
+        .collect()
+}
+
+/// Sums of at least this many terms are solved through their linear form
+/// rather than column by column; with many addends, and especially repeated
+/// words, the coefficient bounds prune far more than single columns can.
+const LINEAR_MIN_TERMS: usize = 3;
+
+/// Reduces an equation of signed terms to one integer coefficient per letter,
+/// in the order of `letters`, such that the equation holds exactly when
+/// `sum(coefficient * digit) == 0`.
+///
+/// Each occurrence of a letter adds its positional weight `base^i`, with the
+/// sign of its term; occurrences in the result are subtracted. Returns `None`
+/// if `sum(|coefficient|) * (base - 1)`, the largest value a partial sum or
+/// bound of the search can reach, does not fit in an `i128`.
+fn linear_coefficients(
+    terms: &[Term],
+    result: &str,
+    letters: &[char],
+    base: u32,
+) -> Option<Vec<i128>> {
+    let mut coefficients: Vec<i128> = vec![0; letters.len()];
+    let words = terms
+        .iter()
+        .map(|term| (term.sign.coefficient(), term.word.as_str()))
+        .chain(std::iter::once((-1, result)));
+    for (sign, word) in words {
+        for (i, c) in word.chars().rev().enumerate() {
+            let weight: i128 = i128::from(base).checked_pow(i as u32)? * i128::from(sign);
+            let letter: usize = letters.iter().position(|&l| l == c).unwrap();
+            coefficients[letter] = coefficients[letter].checked_add(weight)?;
+        }
+    }
+    coefficients
+        .iter()
+        .try_fold(0i128, |total, c| total.checked_add(c.checked_abs()?))?
+        .checked_mul(i128::from(base - 1))?;
+    Some(coefficients)
+}
+
+/// Builds a search for `sum(coefficient * digit) == 0`, reordering `letters`
+/// (and their coefficients) by decreasing magnitude so the bounds prune early.
+fn linear_solver(
+    letters: &mut Vec<char>,
+    coefficients: Vec<i128>,
+    leading: &HashSet<char>,
+    base: u32,
+) -> BacktrackSolver {
+    let mut order: Vec<usize> = (0..letters.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(coefficients[i].abs()));
+    *letters = order.iter().map(|&i| letters[i]).collect();
+    let coefficients: Vec<i128> = order.iter().map(|&i| coefficients[i]).collect();
+
+    let mut solver =
+        BacktrackSolver::new(letters.iter().map(|c| leading.contains(c)).collect(), base);
+    add_linear_checks(&mut solver, &coefficients, base);
+    solver
+}
+
+/// A single column of the equation, counted from the least significant digit.
+#[derive(Clone)]
+struct Column {
+    /// Indices of the letters that the terms contribute to this column, with their sign.
+    addends: Vec<(usize, i64)>,
+    /// Index of the result letter in this column, or `None` past its most significant digit.
+    result: Option<usize>,
+}
+
+/// One decision point of the column-wise search.
+#[derive(Clone)]
+enum Step {
+    /// Try every free digit for a term letter seen here for the first time.
+    Choose(usize),
+    /// Add up a column; the result letter is derived (if new) or checked against the sum.
+    Balance(usize),
+}
+
+/// Backtracking search that assigns letters column by column, tracking the carry.
+///
+/// Subtracted terms make a column sum negative, which shows up as a negative
+/// carry (a borrow) into the next column. Term letters of a column are assigned
+/// first; the result digit then follows from the column sum, so a column that
+/// cannot balance is pruned immediately instead of after the whole mapping has
+/// been built. The search is driven by an explicit stack of steps so it can be
+/// paused after each solution and resumed.
+#[derive(Clone)]
+pub(crate) struct ColumnSolver {
+    columns: Vec<Column>,
+    steps: Vec<Step>,
+    nonzero: Vec<bool>,
+    assignment: Vec<Option<u32>>,
+    base: u32,
+    used: Vec<bool>,
+    /// Carry into each column (negative for a borrow); one extra slot holds the
+    /// carry out of the last one.
+    carries: Vec<i64>,
+    /// Next candidate digit of each step.
+    cursors: Vec<u32>,
+    /// Letter assigned by each step, to be undone when the step is retried.
+    assigned: Vec<Option<usize>>,
+    /// One past the last digit tried by the first step; below `base` in a split search.
+    end: u32,
+    interrupt: Interrupt,
+    stats: Stats,
+    /// Why the search ended early, if it did.
+    stopped: Option<Stop>,
+    depth: usize,
+    done: bool,
+}
+
+impl ColumnSolver {
+    /// Builds the columns of the puzzle, indexing letters by their position in `letters`.
+    fn new(
+        terms: &[Term],
+        result: &str,
+        letters: &[char],
+        leading: &HashSet<char>,
+        base: u32,
+    ) -> Self {
+        let index = |c: char| letters.iter().position(|&l| l == c).unwrap();
+        let width: usize = terms
+            .iter()
+            .map(|term| term.word.as_str())
+            .chain(std::iter::once(result))
+            .map(|word| word.chars().count())
+            .max()
+            .unwrap_or(0);
+        let columns: Vec<Column> = (0..width)
+            .map(|i| Column {
+                addends: terms
+                    .iter()
+                    .filter_map(|term| {
+                        let letter: char = term.word.chars().rev().nth(i)?;
+                        Some((index(letter), term.sign.coefficient()))
+                    })
+                    .collect(),
+                result: result.chars().rev().nth(i).map(index),
+            })
+            .collect();
+
+        let mut seen: Vec<bool> = vec![false; letters.len()];
+        let mut steps: Vec<Step> = Vec::new();
+        for (col, column) in columns.iter().enumerate() {
+            for &(letter, _) in &column.addends {
+                if !seen[letter] {
+                    seen[letter] = true;
+                    steps.push(Step::Choose(letter));
+                }
+            }
+            steps.push(Step::Balance(col));
+            if let Some(letter) = column.result {
+                seen[letter] = true;
+            }
+        }
+
+        ColumnSolver {
+            nonzero: letters.iter().map(|c| leading.contains(c)).collect(),
+            assignment: vec![None; letters.len()],
+            base,
+            used: vec![false; base as usize],
+            carries: vec![0; columns.len() + 1],
+            cursors: vec![0; steps.len()],
+            assigned: vec![None; steps.len()],
+            end: base,
+            interrupt: Interrupt::default(),
+            stats: Stats::default(),
+            stopped: None,
+            depth: 0,
+            done: false,
+            columns,
+            steps,
+        }
+    }
+
+    /// Returns the rule that forbids giving `digit` to `letter`, if any.
+    fn rejection(&self, letter: usize, digit: u32) -> Option<Rule> {
+        if self.used[digit as usize] {
+            Some(Rule::DigitTaken)
+        } else if digit == 0 && self.nonzero[letter] {
+            Some(Rule::LeadingZero)
+        } else {
+            None
+        }
+    }
+
+    fn assign(&mut self, step: usize, letter: usize, digit: u32) {
+        self.assignment[letter] = Some(digit);
+        self.used[digit as usize] = true;
+        self.assigned[step] = Some(letter);
+    }
+
+    /// Undoes the assignment made by `step`, if any.
+    fn unassign(&mut self, step: usize) {
+        if let Some(letter) = self.assigned[step].take() {
+            let digit: u32 = self.assignment[letter].take().unwrap();
+            self.used[digit as usize] = false;
+        }
+    }
+
+    /// Moves `step` on to its next candidate, returning `false` once it has none left.
+    fn try_step(&mut self, step: usize) -> bool {
+        self.unassign(step);
+        match self.steps[step] {
+            Step::Choose(letter) => {
+                let end: u32 = if step == 0 { self.end } else { self.base };
+                while self.cursors[step] < end {
+                    let digit: u32 = self.cursors[step];
+                    self.cursors[step] += 1;
+                    self.stats.nodes += 1;
+                    match self.rejection(letter, digit) {
+                        Some(rule) => self.stats.prunes.count(rule),
+                        None => {
+                            self.assign(step, letter, digit);
+                            return true;
+                        }
+                    }
+                }
+                false
+            }
+            Step::Balance(col) => {
+                if self.cursors[step] > 0 {
+                    return false;
+                }
+                self.cursors[step] = 1;
+                self.stats.nodes += 1;
+                let column: &Column = &self.columns[col];
+                let sum: i64 = self.carries[col]
+                    + column
+                        .addends
+                        .iter()
+                        .map(|&(letter, sign)| sign * i64::from(self.assignment[letter].unwrap()))
+                        .sum::<i64>();
+                let base: i64 = i64::from(self.base);
+                let (digit, carry) = (sum.rem_euclid(base) as u32, sum.div_euclid(base));
+                if col + 1 == self.columns.len() && carry != 0 {
+                    self.stats.prunes.count(Rule::Column);
+                    return false;
+                }
+                self.carries[col + 1] = carry;
+                let rule: Rule = match column.result {
+                    None if digit == 0 => return true,
+                    None => Rule::Column,
+                    Some(letter) => match self.assignment[letter] {
+                        Some(assigned) if assigned == digit => return true,
+                        Some(_) => Rule::Column,
+                        None => match self.rejection(letter, digit) {
+                            Some(rule) => rule,
+                            None => {
+                                self.assign(step, letter, digit);
+                                return true;
+                            }
+                        },
+                    },
+                };
+                self.stats.prunes.count(rule);
+                false
+            }
+        }
+    }
+
+    /// Resumes the search and stops at the next solution, leaving it in `assignment`.
+    ///
+    /// Returns `false` once the search space is exhausted.
+    fn next_solution(&mut self) -> bool {
+        if self.done {
+            return false;
+        }
+        if self.steps.is_empty() {
+            self.done = true;
+            return true;
+        }
+        loop {
+            if self.interrupt.poll(&self.stats) {
+                self.stopped = self.interrupt.reason();
+                self.done = true;
+                return false;
+            }
+            if self.try_step(self.depth) {
+                self.stats.max_depth = self.stats.max_depth.max(self.depth + 1);
+                if self.depth + 1 == self.steps.len() {
+                    return true;
+                }
+                self.depth += 1;
+                self.cursors[self.depth] = 0;
+            } else if self.depth == 0 {
+                self.done = true;
+                return false;
+            } else {
+                self.depth -= 1;
+                self.stats.backtracks += 1;
+            }
+        }
+    }
+
+    /// Pairs each letter with its assigned digit, in the order of `letters`.
+    fn mapping(&self, letters: &[char]) -> Vec<(char, u32)> {
+        letters
+            .iter()
+            .zip(&self.assignment)
+            .map(|(&ch, digit)| (ch, digit.unwrap()))
+            .collect()
+    }
+
+    /// Splits a search that has not started into one part per digit of the first
+    /// letter chosen, in order; any other search is returned whole.
+    fn split(self) -> Vec<ColumnSolver> {
+        let fresh: bool = !self.done && self.cursors[0] == 0 && self.end == self.base;
+        if !fresh || !matches!(self.steps.first(), Some(Step::Choose(_))) {
+            return vec![self];
+        }
+        (0..self.base)
+            .map(|digit| {
+                let mut part: ColumnSolver = self.clone();
+                part.cursors[0] = digit;
+                part.end = digit + 1;
+                part
+            })
+            .collect()
+    }
+}
+
+/// The search strategy behind a [`Solutions`] iterator.
+pub(crate) enum Engine {
+    /// Column-wise carry propagation, for sums and differences of words.
+    Columns(ColumnSolver),
+    /// Letter-by-letter backtracking with puzzle-specific checks.
+    Backtrack(BacktrackSolver),
+}
+
+impl Engine {
+    fn next_solution(&mut self) -> bool {
+        match self {
+            Engine::Columns(solver) => solver.next_solution(),
+            Engine::Backtrack(solver) => solver.next_solution(),
+        }
+    }
+
+    fn mapping(&self, letters: &[char]) -> Vec<(char, u32)> {
+        match self {
+            Engine::Columns(solver) => solver.mapping(letters),
+            Engine::Backtrack(solver) => solver.mapping(letters),
+        }
+    }
+
+    /// Splits the search into parts whose solutions, taken in order, are the
+    /// solutions of the whole search.
+    fn split(self) -> Vec<Engine> {
+        match self {
+            Engine::Columns(solver) => solver.split().into_iter().map(Engine::Columns).collect(),
+            Engine::Backtrack(solver) => {
+                solver.split().into_iter().map(Engine::Backtrack).collect()
+            }
+        }
+    }
+
+    fn interrupt(&mut self) -> &mut Interrupt {
+        match self {
+            Engine::Columns(solver) => &mut solver.interrupt,
+            Engine::Backtrack(solver) => &mut solver.interrupt,
+        }
+    }
+
+    fn stats(&self) -> &Stats {
+        match self {
+            Engine::Columns(solver) => &solver.stats,
+            Engine::Backtrack(solver) => &solver.stats,
+        }
+    }
+
+    fn stopped(&self) -> Option<Stop> {
+        match self {
+            Engine::Columns(solver) => solver.stopped,
+            Engine::Backtrack(solver) => solver.stopped,
+        }
+    }
+}
+
+/// Full evaluation of a puzzle under a mapping, used to double-check solutions in debug builds.
+pub(crate) type Verify = Box<dyn Fn(&[(char, u32)]) -> bool + Send + Sync>;
+
+/// An iterator over every solution of a puzzle, produced lazily by [`solve_all`].
+///
+/// A search can be bounded with [`Solutions::with_timeout`] or
+/// [`Solutions::with_cancel_token`]; it then ends early, as if it had run out of
+/// solutions, and [`Solutions::stopped`] tells the two cases apart. Its
+/// [`Stats`] can be read at any point, or followed with [`Solutions::with_progress`].
+pub struct Solutions {
+    letters: Vec<char>,
+    /// The position in `letters` of each letter of a solution, in the order it is listed.
+    order: Vec<usize>,
+    /// `None` when the puzzle has more distinct letters than there are digits.
+    engine: Option<Engine>,
+    verify: Verify,
+    base: u32,
+    /// The statistics of the parts of parallel searches that have already
+    /// finished, and the time spent searching so far.
+    stats: Stats,
+    /// Why a parallel search ended early, if it did.
+    stopped: Option<Stop>,
+}
+
+impl Solutions {
+    /// Starts the search for an equation of signed terms in `base`, listing
+    /// the letters of each solution in `order`.
+    pub(crate) fn new(
+        terms: Vec<Term>,
+        result: String,
+        allow_leading_zeros: bool,
+        base: u32,
+        order: LetterOrder,
+    ) -> Self {
+        let words: Vec<&str> = terms
+            .iter()
+            .map(|term| term.word.as_str())
+            .chain(std::iter::once(result.as_str()))
+            .collect();
+        let listed: Vec<char> = order.arrange(&words);
+        let mut letters: Vec<char> = LetterOrder::FirstAppearance.arrange(&words);
+
+        let engine: Option<Engine> = if letters.len() > base as usize {
+            None
+        } else {
+            let leading: HashSet<char> = if allow_leading_zeros {
+                HashSet::new()
+            } else {
+                let words: Vec<String> = terms.iter().map(|term| term.word.clone()).collect();
+                leading_letters(&words, &result)
+            };
+            let coefficients: Option<Vec<i128>> = if terms.len() >= LINEAR_MIN_TERMS {
+                linear_coefficients(&terms, &result, &letters, base)
+            } else {
+                None
+            };
+            Some(match coefficients {
+                Some(coefficients) => {
+                    Engine::Backtrack(linear_solver(&mut letters, coefficients, &leading, base))
+                }
+                None => {
+                    Engine::Columns(ColumnSolver::new(&terms, &result, &letters, &leading, base))
+                }
+            })
+        };
+
+        Solutions::from_engine(
+            letters,
+            engine,
+            Box::new(move |mapping| {
+                is_valid_terms_solution_in_base(&terms, &result, mapping, base)
+            }),
+            base,
+        )
+        .ordered(&listed)
+    }
+
+    /// Wraps a search engine whose solutions assign `base` digits to `letters`, in order.
+    pub(crate) fn from_engine(
+        letters: Vec<char>,
+        engine: Option<Engine>,
+        verify: Verify,
+        base: u32,
+    ) -> Self {
+        Solutions {
+            order: (0..letters.len()).collect(),
+            letters,
+            engine,
+            verify,
+            base,
+            stats: Stats::default(),
+            stopped: None,
+        }
+    }
+
+    /// Lists the letters of each solution in the order of `letters`.
+    pub(crate) fn ordered(mut self, letters: &[char]) -> Self {
+        self.order = letters
+            .iter()
+            .filter_map(|c| self.letters.iter().position(|l| l == c))
+            .collect();
+        self
+    }
+
+    /// Ends the search once `timeout` has passed, counting from this call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use crypto_aritmatic::{Alphametic, Puzzle, Stop};
+    ///
+    /// let puzzle: Puzzle = "SEND + MORE = MONEY".parse().unwrap();
+    /// let mut solutions = puzzle.solutions().with_timeout(Duration::ZERO);
+    /// assert_eq!(solutions.next(), None);
+    /// assert_eq!(solutions.stopped(), Some(Stop::TimedOut));
+    /// ```
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        if let Some(engine) = self.engine.as_mut() {
+            engine.interrupt().deadline = Some(Instant::now() + timeout);
+        }
+        self
+    }
+
+    /// Ends the search once `token` is cancelled, from this thread or another.
+    ///
+    /// # Examples
+    ///
+    /// The search can run on a worker thread while the token stays behind:
+    ///
+    /// ```
+    /// use std::thread;
+    /// use crypto_aritmatic::{Alphametic, CancelToken, Puzzle, Stop};
+    ///
+    /// let token = CancelToken::new();
+    /// let puzzle: Puzzle = "SEND + MORE = MONEY".parse().unwrap();
+    /// let mut solutions = puzzle.solutions().with_cancel_token(token.clone());
+    /// token.cancel();
+    /// let worker = thread::spawn(move || (solutions.by_ref().count(), solutions.stopped()));
+    /// assert_eq!(worker.join().unwrap(), (0, Some(Stop::Cancelled)));
+    /// ```
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Self {
+        if let Some(engine) = self.engine.as_mut() {
+            engine.interrupt().tokens.push(token);
+        }
+        self
+    }
+
+    /// Calls `callback` with the running [`Stats`] of the search about once per
+    /// `interval` while it runs, from whichever thread is searching.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use crypto_aritmatic::{Alphametic, Puzzle};
+    ///
+    /// let puzzle: Puzzle = "SEND + MORE = MONEY".parse().unwrap();
+    /// let mut solutions = puzzle
+    ///     .solutions()
+    ///     .with_progress(Duration::from_secs(1), |stats| eprintln!("{}", stats));
+    /// assert!(solutions.next().is_some());
+    /// ```
+    pub fn with_progress(
+        mut self,
+        interval: Duration,
+        callback: impl FnMut(&Stats) + Send + 'static,
+    ) -> Self {
+        if let Some(engine) = self.engine.as_mut() {
+            let progress: Progress = Progress::new(interval, Box::new(callback));
+            engine.interrupt().progress = Some(Arc::new(progress));
+        }
+        self
+    }
+
+    /// Returns why the search ended early, or `None` if it has not (yet).
+    ///
+    /// When the iterator returns `None` and this is `None` too, every solution
+    /// has been found.
+    pub fn stopped(&self) -> Option<Stop> {
+        self.stopped
+            .or_else(|| self.engine.as_ref().and_then(Engine::stopped))
+    }
+
+    /// Returns the number of nodes of the search tree visited so far: one for
+    /// each digit tried for a letter, and one for each column of a sum checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Puzzle};
+    ///
+    /// let puzzle: Puzzle = "SEND + MORE = MONEY".parse().unwrap();
+    /// let mut solutions = puzzle.solutions();
+    /// assert_eq!(solutions.nodes(), 0);
+    /// assert_eq!(solutions.by_ref().count(), 1);
+    /// assert!(solutions.nodes() > 0);
+    /// ```
+    pub fn nodes(&self) -> u64 {
+        self.stats().nodes
+    }
+
+    /// Returns the statistics of the search so far; see [`Stats`].
+    pub fn stats(&self) -> Stats {
+        let mut stats: Stats = self.stats.clone();
+        if let Some(engine) = &self.engine {
+            stats.add(engine.stats());
+        }
+        stats
+    }
+
+    /// Reduces the remaining solutions to a [`Uniqueness`] verdict, stopping after the second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Equation, Uniqueness};
+    ///
+    /// let equation: Equation = "A + B = CD".parse().unwrap();
+    /// assert!(matches!(equation.solutions().uniqueness(), Uniqueness::Multiple(_, _)));
+    /// ```
+    pub fn uniqueness(&mut self) -> Uniqueness {
+        let first: Option<Solution> = self.next();
+        Uniqueness::of(first, self.next())
+    }
+
+    /// Returns the first `n` remaining solutions, in the order the iterator would
+    /// yield them, searching with `threads` threads (0 for one per available core).
+    ///
+    /// A search that has not started is split by the digit of its first letter,
+    /// and the parts run in parallel. Each part stops after `n` solutions, and
+    /// once one has found them, the parts after it are cancelled. The search
+    /// ends with this call: the iterator yields nothing more, and
+    /// [`Solutions::nodes`] and [`Solutions::stopped`] cover every part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Puzzle};
+    ///
+    /// let puzzle: Puzzle = "SEND + MORE = MONEY".parse().unwrap();
+    /// let puzzle = puzzle.allow_leading_zeros(true);
+    /// let parallel = puzzle.solutions().take_parallel(10, 4);
+    /// let sequential: Vec<_> = puzzle.solutions().take(10).collect();
+    /// assert_eq!(parallel, sequential);
+    /// ```
+    pub fn take_parallel(&mut self, n: usize, threads: usize) -> Vec<Solution> {
+        let letters: Vec<char> = self.letters.clone();
+        let mappings: Vec<Vec<(char, u32)>> = self
+            .run_parallel(
+                threads,
+                |engine| {
+                    let mut found: Vec<Vec<(char, u32)>> = Vec::new();
+                    while found.len() < n && engine.next_solution() {
+                        found.push(engine.mapping(&letters));
+                    }
+                    found
+                },
+                |found| found.len() >= n,
+            )
+            .into_iter()
+            .flatten()
+            .flatten()
+            .take(n)
+            .collect();
+        mappings
+            .into_iter()
+            .map(|mapping| self.solution(mapping))
+            .collect()
+    }
+
+    /// Counts the remaining solutions with `threads` threads (0 for one per available core).
+    ///
+    /// Like [`Solutions::take_parallel`], this ends the search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::{Alphametic, Puzzle};
+    ///
+    /// let puzzle: Puzzle = "SEND + MORE = MONEY".parse().unwrap();
+    /// let puzzle = puzzle.allow_leading_zeros(true);
+    /// assert_eq!(puzzle.solutions().count_parallel(0), 25);
+    /// ```
+    pub fn count_parallel(&mut self, threads: usize) -> usize {
+        let counts: Vec<Option<usize>> = self.run_parallel(
+            threads,
+            |engine| {
+                let mut count: usize = 0;
+                while engine.next_solution() {
+                    count += 1;
+                }
+                count
+            },
+            |_| false,
+        );
+        counts.into_iter().flatten().sum()
+    }
+
+    /// Like [`Solutions::uniqueness`], but searches with `threads` threads; see
+    /// [`Solutions::take_parallel`].
+    pub fn uniqueness_parallel(&mut self, threads: usize) -> Uniqueness {
+        let mut solutions = self.take_parallel(2, threads).into_iter();
+        let first: Option<Solution> = solutions.next();
+        Uniqueness::of(first, solutions.next())
+    }
+
+    /// Turns a mapping found by the engine into a [`Solution`], listing its letters in order.
+    fn solution(&self, mapping: Vec<(char, u32)>) -> Solution {
+        debug_assert!((self.verify)(&mapping));
+        let mapping: Vec<(char, u32)> = self.order.iter().map(|&i| mapping[i]).collect();
+        Solution::in_base(mapping, self.base)
+    }
+
+    /// Splits the rest of the search and runs `search` on each part with up to
+    /// `threads` threads (0 for one per available core), returning the results
+    /// in the order of the parts.
+    ///
+    /// Parts are started in order. Once the result of a part satisfies
+    /// `is_final`, every later part is halted; those that had not started give `None`.
+    fn run_parallel<T: Send>(
+        &mut self,
+        threads: usize,
+        search: impl Fn(&mut Engine) -> T + Sync,
+        is_final: impl Fn(&T) -> bool + Sync,
+    ) -> Vec<Option<T>> {
+        let Some(engine) = self.engine.take() else {
+            return Vec::new();
+        };
+        let parts: Vec<Engine> = engine.split();
+        let threads: usize = match threads {
+            0 => thread::available_parallelism().map_or(1, usize::from),
+            threads => threads,
+        };
+        let halts: Vec<Arc<AtomicBool>> = parts
+            .iter()
+            .map(|_| Arc::new(AtomicBool::new(false)))
+            .collect();
+        let results: Mutex<Vec<Option<T>>> = Mutex::new(parts.iter().map(|_| None).collect());
+        let finished: Mutex<(Stats, Option<Stop>)> = Mutex::new((Stats::default(), None));
+        let start: Instant = Instant::now();
+        let queue = Mutex::new(parts.into_iter().enumerate());
+        thread::scope(|scope| {
+            for _ in 0..threads.min(halts.len()) {
+                scope.spawn(|| loop {
+                    let Some((i, mut engine)) = queue.lock().unwrap().next() else {
+                        break;
+                    };
+                    if halts[i].load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    engine.interrupt().halt = Some(Arc::clone(&halts[i]));
+                    let result: T = search(&mut engine);
+                    if is_final(&result) {
+                        for halt in &halts[i + 1..] {
+                            halt.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    results.lock().unwrap()[i] = Some(result);
+                    let mut finished = finished.lock().unwrap();
+                    finished.0.add(engine.stats());
+                    finished.1 = finished.1.or(engine.stopped());
+                });
+            }
+        });
+        let (stats, stopped) = finished.into_inner().unwrap();
+        self.stats.add(&stats);
+        self.stats.elapsed += start.elapsed();
+        self.stopped = self.stopped.or(stopped);
+        results.into_inner().unwrap()
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Solution;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let engine: &mut Engine = self.engine.as_mut()?;
+        let start: Instant = Instant::now();
+        let found: bool = engine.next_solution();
+        self.stats.elapsed += start.elapsed();
+        if !found {
+            return None;
+        }
+        let mapping: Vec<(char, u32)> = engine.mapping(&self.letters);
+        Some(self.solution(mapping))
+    }
+
+    /// Counts the remaining solutions without building a mapping for each one.
+    fn count(self) -> usize {
+        let Some(mut engine) = self.engine else {
+            return 0;
+        };
+        let mut count: usize = 0;
+        while engine.next_solution() {
+            count += 1;
+        }
+        count
+    }
+}
+
+/// Enumerates every solution of the crypto-arithmetic puzzle.
+///
+/// # Parameters
+///
+/// - `words`: A vector of strings representing the words.
+/// - `result`: A string representing the result word.
+/// - `allow_leading_zeros`: If `false`, no multi-letter word may start with a
+///   letter mapped to zero, as in published puzzles.
+///
+/// # Returns
+///
+/// An iterator yielding each valid mapping as a [`Solution`], listing the letters
+/// in the order they first appear. Solutions are found on demand, so taking only
+/// the first few is cheap.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::solve_all;
+///
+/// let words = vec!["TO".to_string(), "GO".to_string()];
+/// let result = "OUT".to_string();
+/// for solution in solve_all(words, result, false) {
+///     println!("{:?}", solution.mapping());
+/// }
+/// ```
+pub fn solve_all(words: Vec<String>, result: String, allow_leading_zeros: bool) -> Solutions {
+    let terms: Vec<Term> = words.into_iter().map(Term::plus).collect();
+    Solutions::new(
+        terms,
+        result,
+        allow_leading_zeros,
+        10,
+        LetterOrder::FirstAppearance,
+    )
+}
+
+/// Counts the solutions of the crypto-arithmetic puzzle.
+///
+/// # Parameters
+///
+/// - `words`: A vector of strings representing the words.
+/// - `result`: A string representing the result word.
+/// - `allow_leading_zeros`: If `false`, no multi-letter word may start with zero.
+///
+/// # Returns
+///
+/// The number of valid mappings.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::count_solutions;
+///
+/// let words = vec!["SEND".to_string(), "MORE".to_string()];
+/// assert_eq!(count_solutions(words, "MONEY".to_string(), false), 1);
+/// ```
+pub fn count_solutions(words: Vec<String>, result: String, allow_leading_zeros: bool) -> usize {
+    solve_all(words, result, allow_leading_zeros).count()
+}
+
+/// Outcome of checking whether a puzzle has exactly one solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Uniqueness {
+    /// The puzzle has no solution.
+    NoSolution,
+    /// The puzzle has exactly one solution.
+    Unique(Solution),
+    /// The puzzle has at least two solutions; the first two found are kept as witnesses.
+    Multiple(Solution, Solution),
+}
+
+impl Uniqueness {
+    /// Classifies a puzzle by its first two solutions.
+    fn of(first: Option<Solution>, second: Option<Solution>) -> Self {
+        match (first, second) {
+            (None, _) => Uniqueness::NoSolution,
+            (Some(solution), None) => Uniqueness::Unique(solution),
+            (Some(first), Some(second)) => Uniqueness::Multiple(first, second),
+        }
+    }
+}
+
+impl fmt::Display for Uniqueness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Uniqueness::NoSolution => write!(f, "no solution"),
+            Uniqueness::Unique(_) => write!(f, "unique"),
+            Uniqueness::Multiple(_, _) => write!(f, "multiple (at least 2)"),
+        }
+    }
+}
+
+/// Checks whether the crypto-arithmetic puzzle has exactly one solution.
+///
+/// The search stops as soon as a second solution is found, so this is much
+/// cheaper than [`count_solutions`] for puzzles with many answers.
+///
+/// # Parameters
+///
+/// - `words`: A vector of strings representing the words.
+/// - `result`: A string representing the result word.
+/// - `allow_leading_zeros`: If `false`, no multi-letter word may start with zero.
+///
+/// # Returns
+///
+/// A [`Uniqueness`] carrying the solution, or two witness solutions if there are several.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{check_uniqueness, Uniqueness};
+///
+/// let words = vec!["SEND".to_string(), "MORE".to_string()];
+/// let result = "MONEY".to_string();
+/// assert!(matches!(check_uniqueness(words.clone(), result.clone(), false), Uniqueness::Unique(_)));
+/// assert!(matches!(check_uniqueness(words, result, true), Uniqueness::Multiple(_, _)));
+/// ```
+pub fn check_uniqueness(
+    words: Vec<String>,
+    result: String,
+    allow_leading_zeros: bool,
+) -> Uniqueness {
+    solve_all(words, result, allow_leading_zeros).uniqueness()
+}
+
+/// Solves the crypto-arithmetic puzzle for the given words and result.
+///
+/// # Parameters
+///
+/// - `words`: A vector of strings representing the words.
+/// - `result`: A string representing the result word.
+/// - `allow_leading_zeros`: If `false`, no multi-letter word may start with a
+///   letter mapped to zero, as in published puzzles.
+///
+/// # Returns
+///
+/// The first [`Solution`] found.
+///
+/// # Errors
+///
+/// Returns [`SolveError::EmptyWord`] or [`SolveError::InvalidCharacter`] for a
+/// malformed word, [`SolveError::TooManyLetters`] if the puzzle has more than 10
+/// distinct letters, and [`SolveError::Unsatisfiable`] if it has no solution.
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{solve_crypto_arithmetic, SolveError};
+///
+/// let words = vec!["SEND".to_string(), "MORE".to_string()];
+/// let result = "MONEY".to_string();
+/// match solve_crypto_arithmetic(words, result, false) {
+///     Ok(solution) => {
+///         for (ch, digit) in solution {
+///             println!("{} = {}", ch, digit);
+///         }
+///     }
+///     Err(error) => println!("No solution found: {}", error),
+/// }
+///
+/// let words = vec!["ABCDEF".to_string(), "GHIJK".to_string()];
+/// assert_eq!(
+///     solve_crypto_arithmetic(words, "LMN".to_string(), false),
+///     Err(SolveError::TooManyLetters { letters: 14, base: 10 })
+/// );
+/// ```
+///
+/// Sums of many words, repeated or not, are reduced to one weighted
+/// coefficient per letter and solved with bound-based pruning:
+///
+/// ```
+/// use crypto_aritmatic::solve_crypto_arithmetic;
+///
+/// let words: Vec<String> = "SO MANY MORE MEN SEEM TO SAY THAT THEY MAY SOON TRY TO STAY AT HOME \
+///     SO AS TO SEE OR HEAR THE SAME ONE MAN TRY TO MEET THE TEAM ON THE MOON AS HE HAS AT THE OTHER TEN"
+///     .split_whitespace()
+///     .map(String::from)
+///     .collect();
+/// let solution = solve_crypto_arithmetic(words, "TESTS".to_string(), false).unwrap();
+/// assert_eq!(solution.value("TESTS"), 90393);
+/// ```
+///
+/// Words too long for the bounds to fit in an `i128` are checked column by
+/// column instead:
+///
+/// ```
+/// use crypto_aritmatic::solve_crypto_arithmetic;
+///
+/// let (a, b, c) = ("A".repeat(38), "B".repeat(38), "C".repeat(38));
+/// let words = vec![a.clone(), a, b];
+/// let solution = solve_crypto_arithmetic(words, c.clone(), false).unwrap();
+/// assert_eq!(2 * solution.value("A") + solution.value("B"), solution.value("C"));
+/// ```
+pub fn solve_crypto_arithmetic(
+    words: Vec<String>,
+    result: String,
+    allow_leading_zeros: bool,
+) -> Result<Solution, SolveError> {
+    Puzzle::new(words, result)
+        .allow_leading_zeros(allow_leading_zeros)
+        .try_solve()
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Counters describing the work done by a search, from [`Solutions::stats`](crate::Solutions::stats).
+///
+/// # Examples
+///
+/// ```
+/// use crypto_aritmatic::{Alphametic, Puzzle};
+///
+/// let puzzle: Puzzle = "SEND + MORE = MONEY".parse().unwrap();
+/// let mut solutions = puzzle.solutions();
+/// assert_eq!(solutions.by_ref().count(), 1);
+/// let stats = solutions.stats();
+/// assert_eq!(stats.nodes, solutions.nodes());
+/// assert!(stats.prunes.total() > 0);
+/// assert!(stats.backtracks > 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Nodes of the search tree visited: one for each digit tried for a letter,
+    /// and one for each column of a sum checked.
+    pub nodes: u64,
+    /// Times the search stepped back to an earlier letter or column.
+    pub backtracks: u64,
+    /// The deepest level of the search tree reached.
+    pub max_depth: usize,
+    /// Branches cut, by the rule that cut them.
+    pub prunes: Prunes,
+    /// Time spent searching.
+    pub elapsed: Duration,
+}
+
+impl Stats {
+    /// Adds the counters of `other` to these, keeping the deeper of the two depths.
+    pub(crate) fn add(&mut self, other: &Stats) {
+        self.nodes += other.nodes;
+        self.backtracks += other.backtracks;
+        self.max_depth = self.max_depth.max(other.max_depth);
+        self.prunes.add(&other.prunes);
+        self.elapsed += other.elapsed;
+    }
+
+    /// Returns the counters gained since `earlier`, a copy of these taken before.
+    pub(crate) fn since(&self, earlier: &Stats) -> Stats {
+        Stats {
+            nodes: self.nodes - earlier.nodes,
+            backtracks: self.backtracks - earlier.backtracks,
+            max_depth: self.max_depth,
+            prunes: self.prunes.since(&earlier.prunes),
+            elapsed: self.elapsed.saturating_sub(earlier.elapsed),
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes, {} backtracks, max depth {}, {} prunes in {:.2?}",
+            self.nodes,
+            self.backtracks,
+            self.max_depth,
+            self.prunes.total(),
+            self.elapsed
+        )
+    }
+}
+
+/// The number of branches cut by each pruning rule of the search.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Prunes {
+    /// The digit was already given to another letter.
+    pub digit_taken: u64,
+    /// The letter starts a word, and the digit was zero.
+    pub leading_zero: u64,
+    /// A column of a sum did not balance with its carry.
+    pub column: u64,
+    /// The letters left could no longer bring a sum of many words to its result.
+    pub bounds: u64,
+    /// The last digits of the two sides of a product or equation differed.
+    pub suffix: u64,
+    /// The full values of the two sides differed.
+    pub value: u64,
+}
+
+impl Prunes {
+    /// Returns the number of branches cut by every rule together.
+    pub fn total(&self) -> u64 {
+        self.rules().iter().map(|&(_, count)| count).sum()
+    }
+
+    /// Returns the name and count of every rule, in a fixed order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crypto_aritmatic::Prunes;
+    ///
+    /// let prunes = Prunes { column: 3, ..Prunes::default() };
+    /// assert_eq!(prunes.rules()[2], ("column", 3));
+    /// ```
+    pub fn rules(&self) -> [(&'static str, u64); 6] {
+        [
+            ("digit taken", self.digit_taken),
+            ("leading zero", self.leading_zero),
+            ("column", self.column),
+            ("bounds", self.bounds),
+            ("suffix", self.suffix),
+            ("value", self.value),
+        ]
+    }
+
+    /// Counts one branch cut by `rule`.
+    pub(crate) fn count(&mut self, rule: Rule) {
+        let counter: &mut u64 = match rule {
+            Rule::DigitTaken => &mut self.digit_taken,
+            Rule::LeadingZero => &mut self.leading_zero,
+            Rule::Column => &mut self.column,
+            Rule::Bounds => &mut self.bounds,
+            Rule::Suffix => &mut self.suffix,
+            Rule::Value => &mut self.value,
+        };
+        *counter += 1;
+    }
+
+    fn add(&mut self, other: &Prunes) {
+        self.digit_taken += other.digit_taken;
+        self.leading_zero += other.leading_zero;
+        self.column += other.column;
+        self.bounds += other.bounds;
+        self.suffix += other.suffix;
+        self.value += other.value;
+    }
+
+    fn since(&self, earlier: &Prunes) -> Prunes {
+        Prunes {
+            digit_taken: self.digit_taken - earlier.digit_taken,
+            leading_zero: self.leading_zero - earlier.leading_zero,
+            column: self.column - earlier.column,
+            bounds: self.bounds - earlier.bounds,
+            suffix: self.suffix - earlier.suffix,
+            value: self.value - earlier.value,
+        }
+    }
+}
+
+/// A rule that cuts a branch of the search; see the fields of [`Prunes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rule {
+    DigitTaken,
+    LeadingZero,
+    Column,
+    Bounds,
+    Suffix,
+    Value,
+}
+
+/// A function given the running totals of a search by [`Progress`].
+pub(crate) type Callback = Box<dyn FnMut(&Stats) + Send>;
+
+/// A callback given the running totals of a search, at most once per `interval`.
+///
+/// The parts of a split search report what they did since their last report,
+/// so the totals cover every thread.
+pub(crate) struct Progress {
+    callback: Mutex<Callback>,
+    interval: Duration,
+    start: Instant,
+    /// The totals so far, and when the callback was last called.
+    state: Mutex<(Stats, Instant)>,
+}
+
+impl Progress {
+    pub(crate) fn new(interval: Duration, callback: Callback) -> Self {
+        let start: Instant = Instant::now();
+        Progress {
+            callback: Mutex::new(callback),
+            interval,
+            start,
+            state: Mutex::new((Stats::default(), start)),
+        }
+    }
+
+    /// Adds the work done since the last report, and calls the callback if it is due.
+    pub(crate) fn report(&self, delta: &Stats) {
+        let mut state = self.state.lock().unwrap();
+        state.0.add(delta);
+        let now: Instant = Instant::now();
+        if now.duration_since(state.1) < self.interval {
+            return;
+        }
+        state.1 = now;
+        let mut totals: Stats = state.0.clone();
+        totals.elapsed = now.duration_since(self.start);
+        drop(state);
+        (self.callback.lock().unwrap())(&totals);
+    }
+}